@@ -0,0 +1,372 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::card::Card;
+use crate::rules::Rules;
+
+// Card counts indexed by blackjack value, 2 through 11 (the Ace)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ShoeComposition {
+    counts: [u32; 12],
+    total: u32,
+}
+
+impl ShoeComposition {
+    pub fn full(decks: usize) -> Self {
+        let decks = decks as u32;
+        let mut counts = [4 * decks; 12];
+        counts[0] = 0;
+        counts[1] = 0;
+        counts[10] = 16 * decks;
+        ShoeComposition {
+            counts,
+            total: 52 * decks,
+        }
+    }
+
+    pub fn from_cards(cards: &[Card]) -> Self {
+        let mut composition = ShoeComposition {
+            counts: [0; 12],
+            total: 0,
+        };
+        for card in cards {
+            composition.counts[card.value() as usize] += 1;
+            composition.total += 1;
+        }
+        composition
+    }
+
+    pub fn count(&self, value: u8) -> u32 {
+        self.counts[value as usize]
+    }
+
+    pub fn total(&self) -> u32 {
+        self.total
+    }
+
+    pub fn probability(&self, value: u8) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        self.count(value) as f64 / self.total as f64
+    }
+
+    // Returns false if there was no such card left to remove
+    pub fn remove(&mut self, value: u8) -> bool {
+        let count = &mut self.counts[value as usize];
+        if *count == 0 {
+            return false;
+        }
+        *count -= 1;
+        self.total -= 1;
+        true
+    }
+
+    pub fn add(&mut self, value: u8) {
+        self.counts[value as usize] += 1;
+        self.total += 1;
+    }
+
+    pub fn without_value(mut self, value: u8) -> Self {
+        self.remove(value);
+        self
+    }
+
+    pub fn without(mut self, cards: &[Card]) -> Self {
+        for card in cards {
+            self.remove(card.value());
+        }
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DealerOutcomes {
+    // Final totals 17 through 21, not counting a blackjack
+    pub totals: [f64; 5],
+    pub blackjack: f64,
+    pub bust: f64,
+}
+
+impl DealerOutcomes {
+    pub fn total(&self, value: u8) -> f64 {
+        match value {
+            17..=21 => self.totals[(value - 17) as usize],
+            _ => 0.0,
+        }
+    }
+
+    pub fn sum(&self) -> f64 {
+        self.totals.iter().sum::<f64>() + self.blackjack + self.bust
+    }
+
+    // The outcomes a player still faces once the dealer has peeked and found no blackjack
+    pub fn given_no_blackjack(&self) -> DealerOutcomes {
+        let remaining = 1.0 - self.blackjack;
+        if remaining <= 0.0 {
+            return DealerOutcomes::default();
+        }
+        DealerOutcomes {
+            totals: self.totals.map(|p| p / remaining),
+            blackjack: 0.0,
+            bust: self.bust / remaining,
+        }
+    }
+}
+
+impl fmt::Display for DealerOutcomes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, p) in self.totals.iter().enumerate() {
+            write!(f, "{}: {:5.1}%  ", 17 + i, p * 100.0)?;
+        }
+        write!(
+            f,
+            "BJ: {:5.1}%  Bust: {:5.1}%",
+            self.blackjack * 100.0,
+            self.bust * 100.0
+        )
+    }
+}
+
+pub(crate) fn add_card(total: u8, soft_aces: u8, value: u8) -> (u8, u8) {
+    let mut total = total + value;
+    let mut soft_aces = soft_aces + u8::from(value == 11);
+    while total > 21 && soft_aces > 0 {
+        total -= 10;
+        soft_aces -= 1;
+    }
+    (total, soft_aces)
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Step {
+    Draw(usize),
+    Stand(u8),
+    Blackjack,
+    Bust,
+}
+
+#[derive(Debug, Clone)]
+struct DealerNode {
+    // Cards drawn after the upcard, counted by value
+    drawn: [u8; 12],
+    drawn_total: u32,
+    next: [Step; 12],
+}
+
+// Every hand the dealer can hold while still drawing, built once per upcard. The same cards
+// drawn in any order share a node, so working out a shoe visits each hand only once rather
+// than every order the cards could come in.
+#[derive(Debug, Clone)]
+pub struct DealerGraph {
+    // In order of cards drawn, so each node comes after every hand it can be drawn from
+    nodes: Vec<DealerNode>,
+}
+
+impl DealerGraph {
+    pub fn new(upcard: u8, rules: &Rules) -> Self {
+        let (total, soft_aces) = add_card(0, 0, upcard);
+        let mut nodes = vec![DealerNode {
+            drawn: [0; 12],
+            drawn_total: 0,
+            next: [Step::Bust; 12],
+        }];
+        let mut hands = vec![(total, soft_aces)];
+        let mut index = HashMap::new();
+
+        let mut i = 0;
+        while i < nodes.len() {
+            let (total, soft_aces) = hands[i];
+            for value in 2..=11u8 {
+                let (next_total, next_soft) = add_card(total, soft_aces, value);
+                let cards = nodes[i].drawn_total + 2;
+                let step = if next_total > 21 {
+                    Step::Bust
+                } else if !rules.dealer_hits_total(next_total, next_soft > 0) {
+                    if cards == 2 && next_total == 21 {
+                        Step::Blackjack
+                    } else {
+                        Step::Stand(next_total)
+                    }
+                } else {
+                    let mut drawn = nodes[i].drawn;
+                    drawn[value as usize] += 1;
+                    let next = *index.entry(drawn).or_insert_with(|| {
+                        nodes.push(DealerNode {
+                            drawn,
+                            drawn_total: cards - 1,
+                            next: [Step::Bust; 12],
+                        });
+                        hands.push((next_total, next_soft));
+                        nodes.len() - 1
+                    });
+                    Step::Draw(next)
+                };
+                nodes[i].next[value as usize] = step;
+            }
+            i += 1;
+        }
+
+        DealerGraph { nodes }
+    }
+
+    // The shoe holds the cards still to be dealt, with the upcard already removed
+    pub fn outcomes(&self, shoe: &ShoeComposition) -> DealerOutcomes {
+        let mut outcomes = DealerOutcomes::default();
+        let mut reach = vec![0.0; self.nodes.len()];
+        reach[0] = 1.0;
+
+        for (i, node) in self.nodes.iter().enumerate() {
+            let probability = reach[i];
+            if probability == 0.0 {
+                continue;
+            }
+            let remaining = (shoe.total() - node.drawn_total) as f64;
+            for value in 2..=11 {
+                let count = shoe.count(value as u8) - node.drawn[value] as u32;
+                if count == 0 {
+                    continue;
+                }
+                let p = probability * count as f64 / remaining;
+                match node.next[value] {
+                    Step::Draw(next) => reach[next] += p,
+                    Step::Stand(total) => outcomes.totals[(total - 17) as usize] += p,
+                    Step::Blackjack => outcomes.blackjack += p,
+                    Step::Bust => outcomes.bust += p,
+                }
+            }
+        }
+        outcomes
+    }
+}
+
+pub fn bust_probability(hand_total: u8, soft: bool, shoe: &ShoeComposition) -> f64 {
+    let soft_aces = u8::from(soft);
+    (2..=11)
+        .filter(|&card| add_card(hand_total, soft_aces, card).0 > 21)
+        .map(|card| shoe.probability(card))
+        .sum()
+}
+
+// Exact probabilities of the dealer's final hand. The shoe holds the cards still
+// to be dealt, so the upcard (and anything else on the table) should already be removed.
+pub fn dealer_outcomes(upcard: u8, shoe: &ShoeComposition, rules: &Rules) -> DealerOutcomes {
+    DealerGraph::new(upcard, rules).outcomes(shoe)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::{Rank, Suit};
+    use crate::deck::Deck;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn test_full_shoe_composition() {
+        let shoe = ShoeComposition::full(6);
+        assert_eq!(shoe.total(), 312);
+        assert_eq!(shoe.count(10), 96);
+        assert_eq!(shoe.count(11), 24);
+        assert_eq!(shoe.count(2), 24);
+
+        let deck = Deck::new();
+        assert_eq!(
+            ShoeComposition::from_cards(deck.cards()),
+            ShoeComposition::full(1)
+        );
+    }
+
+    #[test]
+    fn test_remove_and_add() {
+        let mut shoe = ShoeComposition::full(1);
+        let upcard = Card {
+            suit: Suit::Hearts,
+            rank: Rank::King,
+        };
+        shoe = shoe.without(&[upcard]);
+        assert_eq!(shoe.count(10), 15);
+        assert_eq!(shoe.total(), 51);
+
+        for _ in 0..4 {
+            assert!(shoe.remove(11));
+        }
+        assert!(!shoe.remove(11));
+        assert_eq!(shoe.probability(11), 0.0);
+
+        shoe.add(11);
+        assert_eq!(shoe.count(11), 1);
+    }
+
+    #[test]
+    fn test_probabilities_sum_to_one() {
+        let rules = Rules::default();
+        for upcard in 2..=11 {
+            let mut shoe = ShoeComposition::full(6);
+            shoe.remove(upcard);
+            let outcomes = dealer_outcomes(upcard, &shoe, &rules);
+            assert!(close(outcomes.sum(), 1.0), "upcard {upcard}");
+        }
+    }
+
+    #[test]
+    fn test_blackjack_probability_is_exact() {
+        let rules = Rules::default();
+        let mut shoe = ShoeComposition::full(1);
+        shoe.remove(10);
+
+        // Only an Ace in the hole makes blackjack under a ten
+        let outcomes = dealer_outcomes(10, &shoe, &rules);
+        assert!(close(outcomes.blackjack, 4.0 / 51.0));
+
+        let mut shoe = ShoeComposition::full(1);
+        shoe.remove(11);
+        let outcomes = dealer_outcomes(11, &shoe, &rules);
+        assert!(close(outcomes.blackjack, 16.0 / 51.0));
+    }
+
+    #[test]
+    fn test_known_bust_rates() {
+        let rules = Rules::default();
+        let mut shoe = ShoeComposition::full(6);
+        shoe.remove(6);
+
+        // Published six-deck S17 figure for a dealer six is about 42%
+        let outcomes = dealer_outcomes(6, &shoe, &rules);
+        assert!((0.41..0.43).contains(&outcomes.bust));
+
+        // Hitting soft 17 makes the dealer bust more often with a six up
+        let h17 = Rules {
+            dealer_hits_soft_17: true,
+            ..rules
+        };
+        assert!(dealer_outcomes(6, &shoe, &h17).bust > outcomes.bust);
+        assert_eq!(outcomes.total(17), outcomes.totals[0]);
+    }
+
+    #[test]
+    fn test_bust_probability() {
+        let shoe = ShoeComposition::full(1);
+
+        // Hard 12 busts on any ten-value card
+        assert!(close(bust_probability(12, false, &shoe), 16.0 / 52.0));
+        // Hard 16 busts on 6 through 10
+        assert!(close(bust_probability(16, false, &shoe), 32.0 / 52.0));
+        // Soft hands can't bust on one card
+        assert_eq!(bust_probability(16, true, &shoe), 0.0);
+        assert_eq!(bust_probability(11, false, &shoe), 0.0);
+    }
+
+    #[test]
+    fn test_given_no_blackjack() {
+        let rules = Rules::default();
+        let mut shoe = ShoeComposition::full(6);
+        shoe.remove(11);
+
+        let outcomes = dealer_outcomes(11, &shoe, &rules).given_no_blackjack();
+        assert_eq!(outcomes.blackjack, 0.0);
+        assert!(close(outcomes.sum(), 1.0));
+    }
+}
@@ -0,0 +1,206 @@
+use std::fmt;
+
+use crate::sim::SimStats;
+
+// Bets in units keyed on the floored true count: the first entry covers a true
+// count of 1 or less, each later entry one count higher, and the last everything above
+#[derive(Debug, Clone, PartialEq)]
+pub struct BetRamp {
+    units: Vec<u32>,
+}
+
+impl BetRamp {
+    pub fn new(units: Vec<u32>) -> Option<BetRamp> {
+        if units.is_empty() || units.contains(&0) {
+            return None;
+        }
+        Some(BetRamp { units })
+    }
+
+    // e.g. "1,2,4,6,8"
+    pub fn parse(input: &str) -> Result<BetRamp, String> {
+        let units = input
+            .split(',')
+            .map(|part| part.trim().parse::<u32>())
+            .collect::<Result<Vec<_>, _>>()
+            .ok()
+            .and_then(BetRamp::new);
+        units.ok_or_else(|| format!("Invalid bet ramp: {input} (expected units, e.g. 1,2,4,6,8)"))
+    }
+
+    pub fn bet(&self, true_count: f64) -> u32 {
+        let steps = (true_count.floor() - 1.0).max(0.0) as usize;
+        self.units[steps.min(self.units.len() - 1)]
+    }
+
+    pub fn spread(&self) -> (u32, u32) {
+        let min = self.units.iter().copied().min().unwrap_or(1);
+        let max = self.units.iter().copied().max().unwrap_or(1);
+        (min, max)
+    }
+}
+
+impl fmt::Display for BetRamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let steps: Vec<String> = self
+            .units
+            .iter()
+            .enumerate()
+            .map(|(i, units)| match i {
+                0 => format!("TC<=1: {units}"),
+                i if i == self.units.len() - 1 => format!("TC{}+: {units}", i + 1),
+                i => format!("TC{}: {units}", i + 1),
+            })
+            .collect();
+        let (min, max) = self.spread();
+        write!(f, "1-{} spread ({})", max / min, steps.join(", "))
+    }
+}
+
+// Standard normal CDF, using the Abramowitz and Stegun approximation of erf
+fn normal_cdf(x: f64) -> f64 {
+    let z = x.abs() / std::f64::consts::SQRT_2;
+    let t = 1.0 / (1.0 + 0.327_591_1 * z);
+    let poly = t
+        * (0.254_829_592
+            + t * (-0.284_496_736
+                + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    let erf = 1.0 - poly * (-z * z).exp();
+    if x >= 0.0 {
+        0.5 * (1.0 + erf)
+    } else {
+        0.5 * (1.0 - erf)
+    }
+}
+
+// Figures are in betting units per hand
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BankrollReport {
+    pub win_rate: f64,
+    pub standard_deviation: f64,
+    pub average_bet: f64,
+}
+
+impl BankrollReport {
+    pub fn from_stats(stats: &SimStats) -> BankrollReport {
+        BankrollReport {
+            win_rate: stats.expected_value(),
+            standard_deviation: stats.standard_deviation(),
+            average_bet: stats.average_bet(),
+        }
+    }
+
+    fn variance(&self) -> f64 {
+        self.standard_deviation * self.standard_deviation
+    }
+
+    // Hands needed before the expected win equals one standard deviation
+    pub fn n0(&self) -> Option<f64> {
+        (self.win_rate > 0.0).then(|| self.variance() / (self.win_rate * self.win_rate))
+    }
+
+    // Win rate per 100 hands with a 10,000 unit bankroll bet optimally
+    pub fn score(&self) -> f64 {
+        if self.win_rate <= 0.0 || self.variance() == 0.0 {
+            return 0.0;
+        }
+        1_000_000.0 * self.win_rate * self.win_rate / self.variance()
+    }
+
+    // Chance of ever losing the whole bankroll when playing on forever
+    pub fn risk_of_ruin(&self, bankroll: f64) -> f64 {
+        if self.win_rate <= 0.0 {
+            return 1.0;
+        }
+        if self.variance() == 0.0 {
+            return 0.0;
+        }
+        (-2.0 * self.win_rate * bankroll / self.variance())
+            .exp()
+            .min(1.0)
+    }
+
+    // Chance of losing the whole bankroll at some point within the given number of hands
+    pub fn risk_of_ruin_within(&self, bankroll: f64, hands: u64) -> f64 {
+        let n = hands as f64;
+        let spread = self.standard_deviation * n.sqrt();
+        if spread == 0.0 {
+            return if self.win_rate * n <= -bankroll {
+                1.0
+            } else {
+                0.0
+            };
+        }
+        let drift = self.win_rate * n;
+        let ruin = normal_cdf((-bankroll - drift) / spread)
+            + (-2.0 * self.win_rate * bankroll / self.variance()).exp()
+                * normal_cdf((-bankroll + drift) / spread);
+        ruin.clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64, tolerance: f64) -> bool {
+        (a - b).abs() < tolerance
+    }
+
+    #[test]
+    fn test_bet_ramp() {
+        let ramp = BetRamp::parse("1, 2,4,6,8").expect("valid ramp");
+        assert_eq!(ramp.bet(-3.0), 1);
+        assert_eq!(ramp.bet(1.9), 1);
+        assert_eq!(ramp.bet(2.0), 2);
+        assert_eq!(ramp.bet(3.5), 4);
+        assert_eq!(ramp.bet(5.0), 8);
+        assert_eq!(ramp.bet(12.0), 8);
+        assert_eq!(ramp.spread(), (1, 8));
+        assert!(ramp.to_string().starts_with("1-8 spread"));
+
+        assert!(BetRamp::parse("1,0,2").is_err());
+        assert!(BetRamp::parse("1,x").is_err());
+        assert!(BetRamp::parse("").is_err());
+    }
+
+    #[test]
+    fn test_normal_cdf() {
+        assert!(close(normal_cdf(0.0), 0.5, 1e-7));
+        assert!(close(normal_cdf(1.96), 0.975, 1e-4));
+        assert!(close(normal_cdf(-1.0), 0.158_655, 1e-6));
+    }
+
+    #[test]
+    fn test_metrics() {
+        let report = BankrollReport {
+            win_rate: 0.02,
+            standard_deviation: 3.0,
+            average_bet: 2.5,
+        };
+
+        assert!(close(report.n0().unwrap(), 22_500.0, 1e-6));
+        assert!(close(report.score(), 44.444, 1e-3));
+        // exp(-2 * 0.02 * 500 / 9)
+        assert!(close(report.risk_of_ruin(500.0), 0.108_368, 1e-6));
+
+        // Playing only a few hands can't use up the risk of playing forever
+        let short = report.risk_of_ruin_within(500.0, 1_000);
+        assert!(short < report.risk_of_ruin(500.0));
+        let long = report.risk_of_ruin_within(500.0, 100_000_000);
+        assert!(close(long, report.risk_of_ruin(500.0), 1e-4));
+    }
+
+    #[test]
+    fn test_losing_game_always_goes_broke() {
+        let report = BankrollReport {
+            win_rate: -0.005,
+            standard_deviation: 1.15,
+            average_bet: 1.0,
+        };
+        assert_eq!(report.n0(), None);
+        assert_eq!(report.score(), 0.0);
+        assert_eq!(report.risk_of_ruin(1_000.0), 1.0);
+        assert!(report.risk_of_ruin_within(1_000.0, 10_000_000) > 0.99);
+    }
+}
@@ -0,0 +1,334 @@
+use std::fmt;
+
+// Approximate Hi-Lo player edge: about -0.5% off the top, and +0.5% per true count
+const EDGE_PER_TRUE_COUNT: f64 = 0.005;
+// Variance of a blackjack hand, in units squared
+const HAND_VARIANCE: f64 = 1.33;
+
+// Chooses the next wager. Bets are in chips (or units in the simulator) and are
+// capped by the caller to what the bankroll and table allow.
+pub trait BettingStrategy {
+    fn next_bet(&mut self, bankroll: f64, true_count: f64) -> u32;
+    // Net amount won on the last bet, negative for a loss
+    fn record(&mut self, bet: u32, net: f64);
+}
+
+pub struct Flat {
+    base: u32,
+}
+
+impl BettingStrategy for Flat {
+    fn next_bet(&mut self, _bankroll: f64, _true_count: f64) -> u32 {
+        self.base
+    }
+
+    fn record(&mut self, _bet: u32, _net: f64) {}
+}
+
+// Doubles after every loss to win back everything with one win
+pub struct Martingale {
+    base: u32,
+    next: u32,
+}
+
+impl BettingStrategy for Martingale {
+    fn next_bet(&mut self, _bankroll: f64, _true_count: f64) -> u32 {
+        self.next
+    }
+
+    fn record(&mut self, bet: u32, net: f64) {
+        if net < 0.0 {
+            self.next = bet.saturating_mul(2);
+        } else if net > 0.0 {
+            self.next = self.base;
+        }
+    }
+}
+
+// Doubles after a win and drops back after three wins in a row or any loss
+pub struct Paroli {
+    base: u32,
+    next: u32,
+    wins: u32,
+}
+
+impl BettingStrategy for Paroli {
+    fn next_bet(&mut self, _bankroll: f64, _true_count: f64) -> u32 {
+        self.next
+    }
+
+    fn record(&mut self, bet: u32, net: f64) {
+        if net > 0.0 && self.wins < 2 {
+            self.wins += 1;
+            self.next = bet.saturating_mul(2);
+        } else if net != 0.0 {
+            self.wins = 0;
+            self.next = self.base;
+        }
+    }
+}
+
+// Adds one base bet after a loss and takes one off after a win
+pub struct DAlembert {
+    base: u32,
+    next: u32,
+}
+
+impl BettingStrategy for DAlembert {
+    fn next_bet(&mut self, _bankroll: f64, _true_count: f64) -> u32 {
+        self.next
+    }
+
+    fn record(&mut self, bet: u32, net: f64) {
+        if net < 0.0 {
+            self.next = bet.saturating_add(self.base);
+        } else if net > 0.0 {
+            self.next = bet.saturating_sub(self.base).max(self.base);
+        }
+    }
+}
+
+// Bets a fraction of the bankroll in proportion to the edge at the current true
+// count, and the table minimum when there is no edge
+pub struct Kelly {
+    base: u32,
+    fraction: f64,
+}
+
+impl Kelly {
+    pub fn edge(true_count: f64) -> f64 {
+        EDGE_PER_TRUE_COUNT * (true_count - 1.0)
+    }
+}
+
+impl BettingStrategy for Kelly {
+    fn next_bet(&mut self, bankroll: f64, true_count: f64) -> u32 {
+        let edge = Kelly::edge(true_count);
+        if edge <= 0.0 {
+            return self.base;
+        }
+        let bet = (bankroll * self.fraction * edge / HAND_VARIANCE).round();
+        (bet as u32).max(self.base)
+    }
+
+    fn record(&mut self, _bet: u32, _net: f64) {}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BettingSystem {
+    Flat,
+    Martingale,
+    Paroli,
+    DAlembert,
+    Kelly(f64),
+}
+
+impl BettingSystem {
+    // "kelly" is full Kelly; "kelly:0.5" bets half as much
+    pub fn parse(input: &str) -> Option<BettingSystem> {
+        let input = input.to_lowercase();
+        let (name, fraction) = match input.split_once(':') {
+            Some((name, fraction)) => (name, Some(fraction)),
+            None => (input.as_str(), None),
+        };
+        match (name, fraction) {
+            ("flat", None) => Some(BettingSystem::Flat),
+            ("martingale", None) => Some(BettingSystem::Martingale),
+            ("paroli", None) => Some(BettingSystem::Paroli),
+            ("dalembert" | "d'alembert", None) => Some(BettingSystem::DAlembert),
+            ("kelly", None) => Some(BettingSystem::Kelly(1.0)),
+            ("kelly", Some(fraction)) => fraction
+                .parse::<f64>()
+                .ok()
+                .filter(|f| *f > 0.0 && *f <= 1.0)
+                .map(BettingSystem::Kelly),
+            _ => None,
+        }
+    }
+
+    pub fn create(&self, base: u32) -> Box<dyn BettingStrategy> {
+        let base = base.max(1);
+        match *self {
+            BettingSystem::Flat => Box::new(Flat { base }),
+            BettingSystem::Martingale => Box::new(Martingale { base, next: base }),
+            BettingSystem::Paroli => Box::new(Paroli {
+                base,
+                next: base,
+                wins: 0,
+            }),
+            BettingSystem::DAlembert => Box::new(DAlembert { base, next: base }),
+            BettingSystem::Kelly(fraction) => Box::new(Kelly { base, fraction }),
+        }
+    }
+
+    pub fn uses_count(&self) -> bool {
+        matches!(self, BettingSystem::Kelly(_))
+    }
+}
+
+impl fmt::Display for BettingSystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BettingSystem::Flat => write!(f, "flat"),
+            BettingSystem::Martingale => write!(f, "Martingale"),
+            BettingSystem::Paroli => write!(f, "Paroli"),
+            BettingSystem::DAlembert => write!(f, "D'Alembert"),
+            BettingSystem::Kelly(fraction) if *fraction == 1.0 => write!(f, "Kelly"),
+            BettingSystem::Kelly(fraction) => write!(f, "{fraction} Kelly"),
+        }
+    }
+}
+
+// Keeps a requested bet within the table limit and what the player has left
+pub fn cap_bet(bet: u32, bankroll: f64, table_max: u32) -> u32 {
+    bet.min(table_max).min(bankroll.max(0.0) as u32)
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SessionStats {
+    pub sessions: u64,
+    pub broke: u64,
+    pub ahead: u64,
+    pub hands: u64,
+    pub hands_before_broke: u64,
+    pub final_bankroll: f64,
+    pub largest_bet: u32,
+}
+
+impl SessionStats {
+    pub fn new() -> Self {
+        SessionStats::default()
+    }
+
+    pub fn record(&mut self, start: f64, end: f64, hands: u64, largest_bet: u32, broke: bool) {
+        self.sessions += 1;
+        self.hands += hands;
+        self.final_bankroll += end;
+        self.largest_bet = self.largest_bet.max(largest_bet);
+        if broke {
+            self.broke += 1;
+            self.hands_before_broke += hands;
+        } else if end > start {
+            self.ahead += 1;
+        }
+    }
+
+    pub fn merge(&mut self, other: &SessionStats) {
+        self.sessions += other.sessions;
+        self.broke += other.broke;
+        self.ahead += other.ahead;
+        self.hands += other.hands;
+        self.hands_before_broke += other.hands_before_broke;
+        self.final_bankroll += other.final_bankroll;
+        self.largest_bet = self.largest_bet.max(other.largest_bet);
+    }
+
+    pub fn rate(&self, count: u64) -> f64 {
+        if self.sessions == 0 {
+            return 0.0;
+        }
+        count as f64 / self.sessions as f64
+    }
+
+    pub fn average_final_bankroll(&self) -> f64 {
+        if self.sessions == 0 {
+            return 0.0;
+        }
+        self.final_bankroll / self.sessions as f64
+    }
+
+    pub fn average_hands_before_broke(&self) -> Option<f64> {
+        (self.broke > 0).then(|| self.hands_before_broke as f64 / self.broke as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play(system: BettingSystem, results: &[f64]) -> Vec<u32> {
+        let mut strategy = system.create(10);
+        let mut bets = Vec::new();
+        for &result in results {
+            let bet = strategy.next_bet(1_000.0, 0.0);
+            bets.push(bet);
+            strategy.record(bet, result * bet as f64);
+        }
+        bets
+    }
+
+    #[test]
+    fn test_progressions() {
+        let results = [-1.0, -1.0, -1.0, 1.0, 0.0, 1.0, 1.0, 1.0, 1.0];
+
+        assert_eq!(play(BettingSystem::Flat, &results), vec![10; 9]);
+        assert_eq!(
+            play(BettingSystem::Martingale, &results),
+            vec![10, 20, 40, 80, 10, 10, 10, 10, 10]
+        );
+        assert_eq!(
+            play(BettingSystem::Paroli, &results),
+            vec![10, 10, 10, 10, 20, 20, 40, 10, 20]
+        );
+        assert_eq!(
+            play(BettingSystem::DAlembert, &results),
+            vec![10, 20, 30, 40, 30, 30, 20, 10, 10]
+        );
+    }
+
+    #[test]
+    fn test_kelly_bets_with_the_count() {
+        let mut kelly = BettingSystem::Kelly(1.0).create(10);
+        assert_eq!(kelly.next_bet(10_000.0, -2.0), 10);
+        assert_eq!(kelly.next_bet(10_000.0, 1.0), 10);
+        // 10,000 * 1.5% / 1.33
+        assert_eq!(kelly.next_bet(10_000.0, 4.0), 113);
+
+        let mut half = BettingSystem::Kelly(0.5).create(10);
+        assert_eq!(half.next_bet(10_000.0, 4.0), 56);
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            BettingSystem::parse("Martingale"),
+            Some(BettingSystem::Martingale)
+        );
+        assert_eq!(
+            BettingSystem::parse("d'alembert"),
+            Some(BettingSystem::DAlembert)
+        );
+        assert_eq!(
+            BettingSystem::parse("kelly:0.5"),
+            Some(BettingSystem::Kelly(0.5))
+        );
+        assert_eq!(BettingSystem::parse("kelly:2"), None);
+        assert_eq!(BettingSystem::parse("fibonacci"), None);
+        assert_eq!(BettingSystem::Kelly(0.5).to_string(), "0.5 Kelly");
+    }
+
+    #[test]
+    fn test_cap_bet() {
+        assert_eq!(cap_bet(640, 1_000.0, 500), 500);
+        assert_eq!(cap_bet(80, 35.5, 500), 35);
+        assert_eq!(cap_bet(10, -5.0, 500), 0);
+    }
+
+    #[test]
+    fn test_session_stats() {
+        let mut stats = SessionStats::new();
+        stats.record(100.0, 0.0, 40, 64, true);
+        stats.record(100.0, 130.0, 1_000, 8, false);
+
+        let mut other = SessionStats::new();
+        other.record(100.0, 90.0, 1_000, 4, false);
+        stats.merge(&other);
+
+        assert_eq!(stats.sessions, 3);
+        assert_eq!(stats.rate(stats.broke), 1.0 / 3.0);
+        assert_eq!(stats.ahead, 1);
+        assert_eq!(stats.largest_bet, 64);
+        assert_eq!(stats.average_final_bankroll(), 220.0 / 3.0);
+        assert_eq!(stats.average_hands_before_broke(), Some(40.0));
+    }
+}
@@ -0,0 +1,219 @@
+use std::env;
+use std::process;
+use std::time::Instant;
+
+use blackjack_cli::bankroll::BankrollReport;
+use blackjack_cli::betting::SessionStats;
+use blackjack_cli::fastsim::{self, FastConfig, SessionConfig};
+use blackjack_cli::sim::{self, SimOptions, SimStats};
+
+const USAGE: &str = "\
+Usage: blackjack-sim [--hands N] [--strategy basic|counting|mimic] [rule options]
+                     [--fast [--threads N] [--seed S]]
+                     [--ramp UNITS [--bankroll UNITS] [--session-hands N]]
+                     [--betting SYSTEM [--sessions N] [--session-hands N]
+                      [--bankroll UNITS] [--table-max UNITS]]
+
+Fast mode plays on compact shoes across all CPU cores. Results are
+repeatable for the same seed and thread count.
+
+Bankroll options:
+  --ramp UNITS         Bet by true count, e.g. 1,2,4,6,8 bets 1 unit at TC 1 or
+                       less and 8 units at TC 5 and above. Plays with index plays
+                       unless --strategy is given, and implies --fast
+  --bankroll UNITS     Bankroll for the risk of ruin, in units
+  --session-hands N    Also show the risk of ruin within N hands
+
+Betting systems:
+  --betting SYSTEM     Play separate sessions betting with flat, martingale,
+                       paroli, dalembert, kelly or kelly:FRACTION and report
+                       how many go broke. Bets start at 1 unit
+  --sessions N         Number of sessions to play (default 10,000)
+  --session-hands N    Hands per session unless broke first (default 1,000)
+  --bankroll UNITS     Starting bankroll for each session (default 100)
+  --table-max UNITS    Largest bet the table allows (default 100)
+
+Rule options:
+  --preset NAME        Start from vegas-strip, atlantic-city, downtown-vegas
+                       or european; later options override it
+  --decks N            Number of decks in the shoe (default 6)
+  --h17 | --s17        Dealer hits or stands on soft 17 (default S17)
+  --das | --no-das     Double after split allowed (default DAS)
+  --surrender | --no-surrender
+                       Late surrender offered (default yes)
+  --payout N:D         Blackjack payout (default 3:2)
+  --resplit-aces       Allow split aces to be split again
+  --max-splits N       Maximum number of hands after splitting (default 4)
+  --no-hole-card       Dealer doesn't check for blackjack (European style)
+  --penetration P      Fraction of the shoe dealt before shuffling (default 0.75)";
+
+// Two-sided 95% confidence
+const Z_95: f64 = 1.96;
+
+fn print_report(options: &SimOptions, stats: &SimStats, seconds: f64) {
+    let (low, high) = stats.confidence_interval(Z_95);
+
+    println!("Rules:              {}", options.rules);
+    println!("Strategy:           {}", options.player);
+    if let (Some(seed), Some(threads)) = (options.seed, options.threads) {
+        let plural = if threads == 1 { "" } else { "s" };
+        println!("Seed:               {seed} on {threads} thread{plural}");
+    }
+    println!("Hands played:       {} in {:.1}s", stats.hands, seconds);
+    println!();
+    if options.ramp.is_some() {
+        // Bets vary with the count, so a percentage per hand has no fixed bet to be a share of
+        println!(
+            "Expected value:     {:+.4} units per hand, {:+.3}% of the total wagered",
+            stats.expected_value(),
+            stats.return_on_wagered() * 100.0
+        );
+        println!(
+            "95% confidence:     {:+.4} to {:+.4} units per hand",
+            low, high
+        );
+    } else {
+        println!(
+            "Expected value:     {:+.3}% per hand",
+            stats.expected_value() * 100.0
+        );
+        println!(
+            "95% confidence:     {:+.3}% to {:+.3}%",
+            low * 100.0,
+            high * 100.0
+        );
+    }
+    println!(
+        "Standard deviation: {:.3} units per hand",
+        stats.standard_deviation()
+    );
+    println!(
+        "Win / loss / push:  {:.2}% / {:.2}% / {:.2}%",
+        stats.rate(stats.wins) * 100.0,
+        stats.rate(stats.losses) * 100.0,
+        stats.rate(stats.pushes) * 100.0
+    );
+}
+
+fn print_bankroll_report(options: &SimOptions, stats: &SimStats) {
+    let report = BankrollReport::from_stats(stats);
+
+    println!();
+    if let Some(ramp) = &options.ramp {
+        println!("Bet ramp:           {ramp}");
+    }
+    println!("Average bet:        {:.2} units", report.average_bet);
+    println!(
+        "Win rate:           {:+.3} units per hand, {:+.2} per 100 hands",
+        report.win_rate,
+        report.win_rate * 100.0
+    );
+    println!(
+        "Standard deviation: {:.3} units per hand, {:.2} per 100 hands",
+        report.standard_deviation,
+        report.standard_deviation * 10.0
+    );
+    match report.n0() {
+        Some(n0) => println!("N0:                 {n0:.0} hands"),
+        None => println!("N0:                 never (no edge)"),
+    }
+    println!("SCORE:              {:.2}", report.score());
+
+    if let Some(bankroll) = options.bankroll {
+        println!(
+            "Risk of ruin:       {:.2}% with {bankroll} units",
+            report.risk_of_ruin(bankroll) * 100.0
+        );
+        if let Some(hands) = options.session_hands {
+            println!(
+                "                    {:.2}% within {hands} hands",
+                report.risk_of_ruin_within(bankroll, hands) * 100.0
+            );
+        }
+    }
+}
+
+fn print_session_report(options: &SimOptions, sessions: &SessionConfig, stats: &SessionStats) {
+    println!("Rules:              {}", options.rules);
+    println!("Strategy:           {}", options.player);
+    println!("Betting system:     {}", sessions.system);
+    println!(
+        "Sessions:           {} of up to {} hands, starting with {} units",
+        stats.sessions, sessions.hands, sessions.bankroll
+    );
+    println!("Table maximum:      {} units", sessions.table_max);
+    println!();
+    println!(
+        "Went broke:         {:.2}% of sessions",
+        stats.rate(stats.broke) * 100.0
+    );
+    if let Some(hands) = stats.average_hands_before_broke() {
+        println!("                    after {hands:.0} hands on average");
+    }
+    println!(
+        "Finished ahead:     {:.2}% of sessions",
+        stats.rate(stats.ahead) * 100.0
+    );
+    println!(
+        "Average bankroll:   {:.2} units at the end ({:+.2})",
+        stats.average_final_bankroll(),
+        stats.average_final_bankroll() - sessions.bankroll
+    );
+    println!("Largest bet:        {} units", stats.largest_bet);
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "--help" || arg == "-h") {
+        println!("{USAGE}");
+        return;
+    }
+
+    let mut options = SimOptions::from_args(&args).unwrap_or_else(|err| {
+        eprintln!("{err}");
+        eprintln!("{USAGE}");
+        process::exit(2);
+    });
+
+    if let Some(system) = options.betting {
+        let config = FastConfig {
+            rules: options.rules,
+            player: options.player,
+            hands: 0,
+            seed: options.seed.unwrap_or_else(rand::random),
+            threads: options.threads.unwrap_or_else(fastsim::available_threads),
+            ramp: None,
+        };
+        let sessions = SessionConfig {
+            system,
+            sessions: options.sessions,
+            hands: options.session_hands.unwrap_or(1_000),
+            bankroll: options.bankroll.unwrap_or(100.0),
+            table_max: options.table_max,
+        };
+        let stats = fastsim::simulate_sessions(&config, &sessions);
+        print_session_report(&options, &sessions, &stats);
+        return;
+    }
+
+    let start = Instant::now();
+    let stats = if options.fast {
+        let config = FastConfig {
+            rules: options.rules,
+            player: options.player,
+            hands: options.hands,
+            seed: *options.seed.get_or_insert_with(rand::random),
+            threads: *options
+                .threads
+                .get_or_insert_with(fastsim::available_threads),
+            ramp: options.ramp.clone(),
+        };
+        fastsim::simulate(&config)
+    } else {
+        sim::simulate(options.rules, options.player, options.hands)
+    };
+    print_report(&options, &stats, start.elapsed().as_secs_f64());
+    if options.ramp.is_some() || options.bankroll.is_some() {
+        print_bankroll_report(&options, &stats);
+    }
+}
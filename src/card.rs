@@ -1,5 +1,8 @@
 use std::fmt;
 
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Suit {
     Hearts,
@@ -25,6 +28,66 @@ pub enum Rank {
     King,
 }
 
+impl Rank {
+    pub fn from_symbol(symbol: &str) -> Option<Rank> {
+        match symbol.to_uppercase().as_str() {
+            "A" => Some(Rank::Ace),
+            "2" => Some(Rank::Two),
+            "3" => Some(Rank::Three),
+            "4" => Some(Rank::Four),
+            "5" => Some(Rank::Five),
+            "6" => Some(Rank::Six),
+            "7" => Some(Rank::Seven),
+            "8" => Some(Rank::Eight),
+            "9" => Some(Rank::Nine),
+            "T" | "10" => Some(Rank::Ten),
+            "J" => Some(Rank::Jack),
+            "Q" => Some(Rank::Queen),
+            "K" => Some(Rank::King),
+            _ => None,
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Rank::Ace => "A",
+            Rank::Two => "2",
+            Rank::Three => "3",
+            Rank::Four => "4",
+            Rank::Five => "5",
+            Rank::Six => "6",
+            Rank::Seven => "7",
+            Rank::Eight => "8",
+            Rank::Nine => "9",
+            Rank::Ten => "T",
+            Rank::Jack => "J",
+            Rank::Queen => "Q",
+            Rank::King => "K",
+        }
+    }
+}
+
+impl Suit {
+    pub fn letter(&self) -> char {
+        match self {
+            Suit::Hearts => 'H',
+            Suit::Diamonds => 'D',
+            Suit::Clubs => 'C',
+            Suit::Spades => 'S',
+        }
+    }
+
+    pub fn from_letter(letter: char) -> Option<Suit> {
+        match letter.to_ascii_uppercase() {
+            'H' => Some(Suit::Hearts),
+            'D' => Some(Suit::Diamonds),
+            'C' => Some(Suit::Clubs),
+            'S' => Some(Suit::Spades),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct Card {
     pub suit: Suit,
@@ -46,6 +109,35 @@ impl Card {
             Rank::Ten | Rank::Jack | Rank::Queen | Rank::King => 10,
         }
     }
+
+    // Two characters, rank then suit, e.g. "AH" or "TS"
+    pub fn code(&self) -> String {
+        format!("{}{}", self.rank.symbol(), self.suit.letter())
+    }
+
+    pub fn from_code(code: &str) -> Option<Card> {
+        let mut chars = code.chars();
+        let rank = Rank::from_symbol(&chars.next()?.to_string())?;
+        let suit = Suit::from_letter(chars.next()?)?;
+        if chars.next().is_some() {
+            return None;
+        }
+        Some(Card { suit, rank })
+    }
+}
+
+// Cards are saved by their code so files stay compact and readable
+impl Serialize for Card {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.code())
+    }
+}
+
+impl<'de> Deserialize<'de> for Card {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let code = String::deserialize(deserializer)?;
+        Card::from_code(&code).ok_or_else(|| D::Error::custom(format!("invalid card: {code}")))
+    }
 }
 
 impl fmt::Display for Card {
@@ -239,9 +331,36 @@ mod tests {
         assert_ne!(Suit::Hearts, Suit::Diamonds);
     }
 
+    #[test]
+    fn test_rank_from_symbol() {
+        assert_eq!(Rank::from_symbol("A"), Some(Rank::Ace));
+        assert_eq!(Rank::from_symbol("t"), Some(Rank::Ten));
+        assert_eq!(Rank::from_symbol("10"), Some(Rank::Ten));
+        assert_eq!(Rank::from_symbol("q"), Some(Rank::Queen));
+        assert_eq!(Rank::from_symbol("1"), None);
+    }
+
     #[test]
     fn test_rank_equality() {
         assert_eq!(Rank::Ace, Rank::Ace);
         assert_ne!(Rank::Ace, Rank::King);
     }
+
+    #[test]
+    fn test_card_codes() {
+        let card = Card {
+            suit: Suit::Spades,
+            rank: Rank::Ten,
+        };
+        assert_eq!(card.code(), "TS");
+        let parsed = Card::from_code("qh").expect("valid code");
+        assert_eq!((parsed.rank, parsed.suit), (Rank::Queen, Suit::Hearts));
+        assert!(Card::from_code("1H").is_none());
+        assert!(Card::from_code("AHH").is_none());
+
+        let json = serde_json::to_string(&card).unwrap();
+        assert_eq!(json, "\"TS\"");
+        let back: Card = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.code(), "TS");
+    }
 }
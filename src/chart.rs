@@ -0,0 +1,500 @@
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::thread;
+
+use crate::analysis::ShoeComposition;
+use crate::card::{Card, Rank, Suit};
+use crate::color::{self, ChartPalette, Color, Theme};
+use crate::ev::{self, ActionValues, EvCalculator};
+use crate::hand::Hand;
+use crate::rules::Rules;
+use crate::strategy::{Action, HandKey};
+
+const UPCARDS: [u8; 10] = [2, 3, 4, 5, 6, 7, 8, 9, 10, 11];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cell {
+    Hit,
+    Stand,
+    DoubleOrHit,
+    DoubleOrStand,
+    Split,
+    SurrenderOrHit,
+    SurrenderOrStand,
+    SurrenderOrSplit,
+}
+
+impl Cell {
+    // The best action, plus what to do instead when doubling or surrendering isn't allowed
+    pub fn from_values(values: &ActionValues) -> Cell {
+        let fallback = |actions: &[Action]| {
+            actions
+                .iter()
+                .filter_map(|&action| Some((action, values.value(action)?)))
+                .fold((Action::Stand, f64::NEG_INFINITY), |best, candidate| {
+                    if candidate.1 > best.1 {
+                        candidate
+                    } else {
+                        best
+                    }
+                })
+                .0
+        };
+
+        match values.best().0 {
+            Action::Hit => Cell::Hit,
+            Action::Stand => Cell::Stand,
+            Action::Split => Cell::Split,
+            Action::Double => match fallback(&[Action::Stand, Action::Hit]) {
+                Action::Stand => Cell::DoubleOrStand,
+                _ => Cell::DoubleOrHit,
+            },
+            Action::Surrender => match fallback(&[Action::Stand, Action::Hit, Action::Split]) {
+                Action::Stand => Cell::SurrenderOrStand,
+                Action::Split => Cell::SurrenderOrSplit,
+                _ => Cell::SurrenderOrHit,
+            },
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Cell::Hit => "H",
+            Cell::Stand => "S",
+            Cell::DoubleOrHit => "Dh",
+            Cell::DoubleOrStand => "Ds",
+            Cell::Split => "P",
+            Cell::SurrenderOrHit => "Rh",
+            Cell::SurrenderOrStand => "Rs",
+            Cell::SurrenderOrSplit => "Rp",
+        }
+    }
+
+    fn colour(&self, palette: &ChartPalette) -> Color {
+        match self {
+            Cell::Hit => palette.hit,
+            Cell::Stand => palette.stand,
+            Cell::DoubleOrHit | Cell::DoubleOrStand => palette.double,
+            Cell::Split => palette.split,
+            Cell::SurrenderOrHit | Cell::SurrenderOrStand | Cell::SurrenderOrSplit => {
+                palette.surrender
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartFormat {
+    Grid,
+    Csv,
+    Markdown,
+}
+
+impl ChartFormat {
+    pub fn parse(input: &str) -> Option<ChartFormat> {
+        match input.to_lowercase().as_str() {
+            "grid" => Some(ChartFormat::Grid),
+            "csv" => Some(ChartFormat::Csv),
+            "markdown" | "md" => Some(ChartFormat::Markdown),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ChartRow {
+    pub hand: HandKey,
+    pub cells: [Cell; 10],
+}
+
+#[derive(Debug, Clone)]
+pub struct StrategyChart {
+    pub rules: Rules,
+    pub hard: Vec<ChartRow>,
+    pub soft: Vec<ChartRow>,
+    pub pairs: Vec<ChartRow>,
+}
+
+fn card(value: u8) -> Card {
+    let rank = match value {
+        11 => Rank::Ace,
+        value => Rank::from_symbol(&value.to_string()).expect("card value between 2 and 10"),
+    };
+    Card {
+        suit: Suit::Spades,
+        rank,
+    }
+}
+
+fn upcard_label(value: u8) -> &'static str {
+    ["2", "3", "4", "5", "6", "7", "8", "9", "T", "A"][(value - 2) as usize]
+}
+
+// The two-card hands that make up each row of the chart
+fn starting_hands(hand: HandKey) -> Vec<(u8, u8)> {
+    match hand {
+        HandKey::Pair(value) => vec![(value, value)],
+        HandKey::Soft(total) => vec![(11, total - 11)],
+        HandKey::Hard(total) => (2..=10)
+            .flat_map(|first| (first + 1..=10).map(move |second| (first, second)))
+            .filter(|(first, second)| first + second == total)
+            .collect(),
+    }
+}
+
+// Averages the values of every way to be dealt the hand, weighted by how likely each is
+fn row_values(
+    calculator: &mut EvCalculator,
+    hand: HandKey,
+    shoe: &ShoeComposition,
+    rules: &Rules,
+) -> ActionValues {
+    let mut weighted: Vec<(f64, ActionValues)> = Vec::new();
+    for (first, second) in starting_hands(hand) {
+        let mut rest = *shoe;
+        let mut weight = shoe.probability(first);
+        rest.remove(first);
+        weight *= rest.probability(second);
+        rest.remove(second);
+        if first != second {
+            weight *= 2.0;
+        }
+        if weight == 0.0 {
+            continue;
+        }
+
+        let mut cards = Hand::new();
+        cards.add_card(card(first));
+        cards.add_card(card(second));
+        let available = ev::available_for(&cards, rules);
+        weighted.push((weight, calculator.action_values(&cards, &rest, available)));
+    }
+
+    let total: f64 = weighted.iter().map(|(weight, _)| weight).sum();
+    let average = |field: fn(&ActionValues) -> Option<f64>| -> Option<f64> {
+        weighted
+            .iter()
+            .map(|(weight, values)| Some(weight * field(values)?))
+            .sum::<Option<f64>>()
+            .map(|sum| sum / total)
+    };
+
+    ActionValues {
+        stand: average(|values| Some(values.stand)).unwrap_or(0.0),
+        hit: average(|values| Some(values.hit)).unwrap_or(0.0),
+        double: average(|values| values.double),
+        split: average(|values| values.split),
+        surrender: average(|values| values.surrender),
+    }
+}
+
+impl StrategyChart {
+    // Each upcard column is worked out on its own thread
+    pub fn calculate(rules: &Rules) -> StrategyChart {
+        let hard: Vec<HandKey> = (5..=17).map(HandKey::Hard).collect();
+        let soft: Vec<HandKey> = (13..=20).map(HandKey::Soft).collect();
+        let pairs: Vec<HandKey> = (2..=11).map(HandKey::Pair).collect();
+
+        let columns: Vec<Vec<Cell>> = thread::scope(|scope| {
+            let workers: Vec<_> = UPCARDS
+                .iter()
+                .map(|&upcard| {
+                    let (hard, soft, pairs) = (&hard, &soft, &pairs);
+                    scope.spawn(move || {
+                        let shoe = ShoeComposition::full(rules.decks).without_value(upcard);
+                        let mut calculator = EvCalculator::new(*rules, upcard);
+                        hard.iter()
+                            .chain(soft)
+                            .chain(pairs)
+                            .map(|&hand| {
+                                Cell::from_values(&row_values(&mut calculator, hand, &shoe, rules))
+                            })
+                            .collect()
+                    })
+                })
+                .collect();
+
+            workers
+                .into_iter()
+                .map(|worker| worker.join().expect("chart thread panicked"))
+                .collect()
+        });
+
+        let rows = |hands: &[HandKey], offset: usize| -> Vec<ChartRow> {
+            hands
+                .iter()
+                .enumerate()
+                .map(|(i, &hand)| ChartRow {
+                    hand,
+                    cells: std::array::from_fn(|column| columns[column][offset + i]),
+                })
+                .collect()
+        };
+
+        StrategyChart {
+            rules: *rules,
+            hard: rows(&hard, 0),
+            soft: rows(&soft, hard.len()),
+            pairs: rows(&pairs, hard.len() + soft.len()),
+        }
+    }
+
+    pub fn sections(&self) -> [(&'static str, &[ChartRow]); 3] {
+        [
+            ("Hard totals", &self.hard),
+            ("Soft totals", &self.soft),
+            ("Pairs", &self.pairs),
+        ]
+    }
+
+    pub fn cell(&self, hand: HandKey, upcard: u8) -> Option<Cell> {
+        let column = UPCARDS.iter().position(|&value| value == upcard)?;
+        self.sections()
+            .iter()
+            .flat_map(|(_, rows)| rows.iter())
+            .find(|row| row.hand == hand)
+            .map(|row| row.cells[column])
+    }
+
+    pub fn render_grid(&self, palette: Option<ChartPalette>) -> String {
+        let mut lines = vec![format!("Basic strategy for {}", self.rules)];
+        for (name, rows) in self.sections() {
+            lines.push(String::new());
+            let header: Vec<String> = UPCARDS
+                .iter()
+                .map(|&upcard| format!("{:^4}", upcard_label(upcard)))
+                .collect();
+            lines.push(format!("{name:<12}{}", header.join("")));
+
+            for row in rows {
+                let cells: Vec<String> = row
+                    .cells
+                    .iter()
+                    .map(|cell| {
+                        let code = format!("{:^4}", cell.code());
+                        color::paint(&code, palette.map(|palette| cell.colour(&palette)))
+                    })
+                    .collect();
+                lines.push(format!("{:<12}{}", row.hand.to_string(), cells.join("")));
+            }
+        }
+        lines.push(String::new());
+        lines.push(LEGEND.to_string());
+        lines.join("\n")
+    }
+
+    pub fn to_csv(&self) -> String {
+        let header: Vec<&str> = UPCARDS.iter().map(|&upcard| upcard_label(upcard)).collect();
+        let mut lines = vec![format!("section,hand,{}", header.join(","))];
+        for (name, rows) in self.sections() {
+            let section = name
+                .split_whitespace()
+                .next()
+                .unwrap_or(name)
+                .to_lowercase();
+            for row in rows {
+                let cells: Vec<&str> = row.cells.iter().map(Cell::code).collect();
+                lines.push(format!("{section},{},{}", row.hand, cells.join(",")));
+            }
+        }
+        lines.join("\n") + "\n"
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let header: Vec<&str> = UPCARDS.iter().map(|&upcard| upcard_label(upcard)).collect();
+        let mut lines = vec![format!("# Basic strategy for {}", self.rules)];
+        for (name, rows) in self.sections() {
+            lines.push(String::new());
+            lines.push(format!("## {name}"));
+            lines.push(String::new());
+            lines.push(format!("| Hand | {} |", header.join(" | ")));
+            lines.push(format!("|------|{}", "---|".repeat(header.len())));
+            for row in rows {
+                let cells: Vec<&str> = row.cells.iter().map(Cell::code).collect();
+                lines.push(format!("| {} | {} |", row.hand, cells.join(" | ")));
+            }
+        }
+        lines.push(String::new());
+        lines.push(LEGEND.to_string());
+        lines.join("\n") + "\n"
+    }
+
+    pub fn render(&self, format: ChartFormat, palette: Option<ChartPalette>) -> String {
+        match format {
+            ChartFormat::Grid => self.render_grid(palette),
+            ChartFormat::Csv => self.to_csv(),
+            ChartFormat::Markdown => self.to_markdown(),
+        }
+    }
+}
+
+const LEGEND: &str = "H = hit, S = stand, Dh/Ds = double (else hit/stand), P = split, \
+Rh/Rs/Rp = surrender (else hit/stand/split)";
+
+impl fmt::Display for StrategyChart {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.render_grid(None))
+    }
+}
+
+pub struct ChartOptions {
+    pub rules: Rules,
+    pub format: ChartFormat,
+    pub output: Option<PathBuf>,
+    pub theme: Option<Theme>,
+}
+
+impl Default for ChartOptions {
+    fn default() -> Self {
+        ChartOptions {
+            rules: Rules::default(),
+            format: ChartFormat::Grid,
+            output: None,
+            theme: None,
+        }
+    }
+}
+
+impl ChartOptions {
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let mut options = ChartOptions::default();
+        let mut iter = args.iter();
+
+        while let Some(arg) = iter.next() {
+            if options.rules.apply_option(arg, &mut iter)? {
+                continue;
+            }
+
+            let value = iter
+                .next()
+                .ok_or_else(|| format!("Missing value for {arg}"))?;
+            match arg.as_str() {
+                "--format" => {
+                    options.format = ChartFormat::parse(value)
+                        .ok_or_else(|| format!("Unknown chart format: {value}"))?
+                }
+                "--output" => options.output = Some(PathBuf::from(value)),
+                "--theme" => {
+                    options.theme = Some(Theme::parse(value).ok_or_else(|| {
+                        let names: Vec<&str> = Theme::ALL.iter().map(Theme::name).collect();
+                        format!("Unknown theme: {value}. Choose from {}", names.join(", "))
+                    })?)
+                }
+                _ => return Err(format!("Unknown option: {arg}")),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+// Prints the chart, coloured unless NO_COLOR is set or the output isn't a terminal, or
+// writes it to the output file without colour codes
+pub fn run(options: &ChartOptions) -> Result<(), String> {
+    let chart = StrategyChart::calculate(&options.rules);
+    match &options.output {
+        Some(path) => fs::write(path, chart.render(options.format, None))
+            .map_err(|err| format!("Couldn't write {}: {err}", path.display())),
+        None => {
+            println!(
+                "{}",
+                chart
+                    .render(options.format, color::chart_palette())
+                    .trim_end()
+            );
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_starting_hands() {
+        assert_eq!(starting_hands(HandKey::Hard(5)), vec![(2, 3)]);
+        assert_eq!(
+            starting_hands(HandKey::Hard(12)),
+            vec![(2, 10), (3, 9), (4, 8), (5, 7)]
+        );
+        assert_eq!(starting_hands(HandKey::Soft(18)), vec![(11, 7)]);
+        assert_eq!(starting_hands(HandKey::Pair(11)), vec![(11, 11)]);
+    }
+
+    #[test]
+    fn test_cell_from_values() {
+        let values = ActionValues {
+            stand: -0.54,
+            hit: -0.53,
+            double: Some(-1.06),
+            split: None,
+            surrender: Some(-0.5),
+        };
+        assert_eq!(Cell::from_values(&values), Cell::SurrenderOrHit);
+
+        let values = ActionValues {
+            stand: 0.12,
+            hit: 0.05,
+            double: Some(0.2),
+            split: None,
+            surrender: None,
+        };
+        assert_eq!(Cell::from_values(&values), Cell::DoubleOrStand);
+    }
+
+    #[test]
+    fn test_chart_options_from_args() {
+        let args: Vec<String> = ["--format", "md", "--theme", "protanopia", "--h17"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        let options = ChartOptions::from_args(&args).unwrap();
+        assert_eq!(options.format, ChartFormat::Markdown);
+        assert_eq!(options.theme, Some(Theme::Protanopia));
+        assert!(options.rules.dealer_hits_soft_17);
+
+        let args = ["--theme".to_string(), "sepia".to_string()];
+        let err = ChartOptions::from_args(&args).err().unwrap();
+        assert!(err.starts_with("Unknown theme: sepia"));
+    }
+
+    #[test]
+    fn test_calculated_chart() {
+        // A single deck keeps the calculation quick without changing these plays
+        let chart = StrategyChart::calculate(&Rules {
+            decks: 1,
+            ..Rules::default()
+        });
+
+        assert_eq!(chart.cell(HandKey::Hard(11), 6), Some(Cell::DoubleOrHit));
+        assert_eq!(
+            chart.cell(HandKey::Hard(16), 10),
+            Some(Cell::SurrenderOrHit)
+        );
+        assert_eq!(chart.cell(HandKey::Hard(12), 4), Some(Cell::Stand));
+        assert_eq!(chart.cell(HandKey::Hard(12), 2), Some(Cell::Hit));
+        assert_eq!(chart.cell(HandKey::Soft(18), 4), Some(Cell::DoubleOrStand));
+        assert_eq!(chart.cell(HandKey::Soft(18), 10), Some(Cell::Hit));
+        assert_eq!(chart.cell(HandKey::Pair(8), 7), Some(Cell::Split));
+        assert_eq!(chart.cell(HandKey::Pair(10), 6), Some(Cell::Stand));
+        assert_eq!(chart.cell(HandKey::Pair(11), 11), Some(Cell::Split));
+
+        let csv = chart.to_csv();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("section,hand,2,3,4,5,6,7,8,9,T,A"));
+        assert_eq!(csv.lines().count(), 1 + 13 + 8 + 10);
+        assert!(csv.contains("\nsoft,A7,"));
+        assert!(csv.contains("\npairs,AA,"));
+
+        let markdown = chart.to_markdown();
+        assert!(markdown.contains("## Pairs"));
+        assert!(markdown.contains("| Hand | 2 | 3 | 4 | 5 | 6 | 7 | 8 | 9 | T | A |"));
+
+        assert!(!chart.render_grid(None).contains('\x1b'));
+        let palette = color::Theme::Deuteranopia.chart_palette();
+        let coloured = chart.render_grid(Some(palette));
+        assert!(coloured.contains(&color::paint(" Dh ", Some(palette.double))));
+    }
+}
@@ -0,0 +1,337 @@
+use std::env;
+use std::ffi::OsString;
+use std::fmt;
+use std::io::{self, IsTerminal};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+
+use crate::card::Suit;
+
+// Off until init() decides, so library callers and tests get plain text
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static THEME: RwLock<Theme> = RwLock::new(Theme::Classic);
+
+// SGR parameters for one colour, e.g. "31" or "38;5;208", or "30;47" for black on white
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Color(&'static str);
+
+impl Color {
+    pub const RED: Color = Color("31");
+    pub const GREEN: Color = Color("32");
+    pub const YELLOW: Color = Color("33");
+    pub const BLUE: Color = Color("34");
+    pub const BOLD: Color = Color("1");
+    pub const BRIGHT_RED: Color = Color("1;91");
+    pub const BRIGHT_GREEN: Color = Color("1;92");
+    pub const BRIGHT_YELLOW: Color = Color("1;93");
+    pub const BRIGHT_CYAN: Color = Color("1;96");
+    pub const BRIGHT_WHITE: Color = Color("1;97");
+    // From the Okabe-Ito palette, which stays distinct for most colour vision
+    pub const ORANGE: Color = Color("38;5;208");
+    pub const SKY_BLUE: Color = Color("38;5;39");
+    pub const OI_BLUE: Color = Color("38;5;32");
+    pub const OI_YELLOW: Color = Color("38;5;220");
+    pub const VERMILLION: Color = Color("38;5;166");
+    pub const BLUISH_GREEN: Color = Color("38;5;36");
+    pub const REDDISH_PURPLE: Color = Color("38;5;175");
+    pub const GRAY: Color = Color("38;5;245");
+    // Black text on a background, for the strategy chart's cells
+    pub const ON_WHITE: Color = Color("30;47");
+    pub const ON_YELLOW: Color = Color("30;43");
+    pub const ON_GREEN: Color = Color("30;42");
+    pub const ON_CYAN: Color = Color("30;46");
+    pub const ON_MAGENTA: Color = Color("30;45");
+    pub const ON_ORANGE: Color = Color("30;48;5;208");
+    pub const ON_SKY_BLUE: Color = Color("30;48;5;39");
+    pub const ON_OI_YELLOW: Color = Color("30;48;5;220");
+    pub const ON_VERMILLION: Color = Color("30;48;5;166");
+    pub const ON_BLUISH_GREEN: Color = Color("30;48;5;36");
+    pub const ON_REDDISH_PURPLE: Color = Color("30;48;5;175");
+    pub const ON_GRAY: Color = Color("30;48;5;245");
+}
+
+// The colour of each suit, the card back and the round results. None leaves
+// the terminal's own colour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palette {
+    pub hearts: Option<Color>,
+    pub diamonds: Option<Color>,
+    pub clubs: Option<Color>,
+    pub spades: Option<Color>,
+    pub back: Option<Color>,
+    pub win: Option<Color>,
+    pub lose: Option<Color>,
+    pub push: Option<Color>,
+}
+
+impl Palette {
+    pub fn suit(&self, suit: Suit) -> Option<Color> {
+        match suit {
+            Suit::Hearts => self.hearts,
+            Suit::Diamonds => self.diamonds,
+            Suit::Clubs => self.clubs,
+            Suit::Spades => self.spades,
+        }
+    }
+}
+
+// The strategy chart's cell colours, one for each kind of play
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChartPalette {
+    pub hit: Color,
+    pub stand: Color,
+    pub double: Color,
+    pub split: Color,
+    pub surrender: Color,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Theme {
+    Classic,
+    FourColor,
+    HighContrast,
+    Deuteranopia,
+    Protanopia,
+    Tritanopia,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 6] = [
+        Theme::Classic,
+        Theme::FourColor,
+        Theme::HighContrast,
+        Theme::Deuteranopia,
+        Theme::Protanopia,
+        Theme::Tritanopia,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Theme::Classic => "classic",
+            Theme::FourColor => "four-color",
+            Theme::HighContrast => "high-contrast",
+            Theme::Deuteranopia => "deuteranopia",
+            Theme::Protanopia => "protanopia",
+            Theme::Tritanopia => "tritanopia",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Theme> {
+        let name = name.to_lowercase().replace("colour", "color");
+        Theme::ALL.into_iter().find(|theme| theme.name() == name)
+    }
+
+    pub fn palette(&self) -> Palette {
+        let classic = Palette {
+            hearts: Some(Color::RED),
+            diamonds: Some(Color::RED),
+            clubs: None,
+            spades: None,
+            back: Some(Color::BLUE),
+            win: Some(Color::GREEN),
+            lose: Some(Color::RED),
+            push: Some(Color::YELLOW),
+        };
+        match self {
+            Theme::Classic => classic,
+            Theme::FourColor => Palette {
+                diamonds: Some(Color::BLUE),
+                clubs: Some(Color::GREEN),
+                back: Some(Color::YELLOW),
+                ..classic
+            },
+            Theme::HighContrast => Palette {
+                hearts: Some(Color::BRIGHT_RED),
+                diamonds: Some(Color::BRIGHT_RED),
+                clubs: Some(Color::BRIGHT_WHITE),
+                spades: Some(Color::BRIGHT_WHITE),
+                back: Some(Color::BRIGHT_CYAN),
+                win: Some(Color::BRIGHT_GREEN),
+                lose: Some(Color::BRIGHT_RED),
+                push: Some(Color::BRIGHT_YELLOW),
+            },
+            // The colour-blind themes are four-colour decks, with spades left in the
+            // terminal's colour. Red and green look alike, so orange is set against blue.
+            Theme::Deuteranopia => Palette {
+                hearts: Some(Color::ORANGE),
+                diamonds: Some(Color::OI_BLUE),
+                clubs: Some(Color::BLUISH_GREEN),
+                back: Some(Color::GRAY),
+                win: Some(Color::SKY_BLUE),
+                lose: Some(Color::ORANGE),
+                push: Some(Color::BOLD),
+                ..classic
+            },
+            // Reds look dark as well, so the bright yellow stands in for them
+            Theme::Protanopia => Palette {
+                hearts: Some(Color::OI_YELLOW),
+                diamonds: Some(Color::SKY_BLUE),
+                clubs: Some(Color::BLUISH_GREEN),
+                back: Some(Color::GRAY),
+                win: Some(Color::OI_BLUE),
+                lose: Some(Color::OI_YELLOW),
+                push: Some(Color::BOLD),
+                ..classic
+            },
+            // Blue and yellow are the ones to avoid, while red still stands out
+            Theme::Tritanopia => Palette {
+                hearts: Some(Color::VERMILLION),
+                diamonds: Some(Color::REDDISH_PURPLE),
+                clubs: Some(Color::BLUISH_GREEN),
+                back: Some(Color::GRAY),
+                win: Some(Color::BLUISH_GREEN),
+                lose: Some(Color::VERMILLION),
+                push: Some(Color::BOLD),
+                ..classic
+            },
+        }
+    }
+
+    pub fn chart_palette(&self) -> ChartPalette {
+        let classic = ChartPalette {
+            hit: Color::ON_WHITE,
+            stand: Color::ON_YELLOW,
+            double: Color::ON_GREEN,
+            split: Color::ON_CYAN,
+            surrender: Color::ON_MAGENTA,
+        };
+        match self {
+            Theme::Classic | Theme::FourColor | Theme::HighContrast => classic,
+            // Green doubles would look like yellow stands, so doubles go blue and
+            // splits orange
+            Theme::Deuteranopia | Theme::Protanopia => ChartPalette {
+                stand: Color::ON_OI_YELLOW,
+                double: Color::ON_SKY_BLUE,
+                split: Color::ON_ORANGE,
+                surrender: Color::ON_REDDISH_PURPLE,
+                ..classic
+            },
+            Theme::Tritanopia => ChartPalette {
+                stand: Color::ON_VERMILLION,
+                double: Color::ON_BLUISH_GREEN,
+                split: Color::ON_REDDISH_PURPLE,
+                surrender: Color::ON_GRAY,
+                ..classic
+            },
+        }
+    }
+}
+
+impl fmt::Display for Theme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+// NO_COLOR turns colour off when it's set to anything but an empty string
+// (https://no-color.org), and so does output that isn't going to a terminal
+pub fn should_color(no_color: Option<OsString>, is_terminal: bool) -> bool {
+    let no_color = no_color.is_some_and(|value| !value.is_empty());
+    is_terminal && !no_color
+}
+
+pub fn init() {
+    set_enabled(should_color(
+        env::var_os("NO_COLOR"),
+        io::stdout().is_terminal(),
+    ));
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn theme() -> Theme {
+    *THEME.read().unwrap_or_else(|err| err.into_inner())
+}
+
+pub fn set_theme(theme: Theme) {
+    *THEME.write().unwrap_or_else(|err| err.into_inner()) = theme;
+}
+
+// The current theme's palette, or None while colour is off
+pub fn palette() -> Option<Palette> {
+    enabled().then(|| theme().palette())
+}
+
+// The current theme's strategy chart colours, or None while colour is off
+pub fn chart_palette() -> Option<ChartPalette> {
+    enabled().then(|| theme().chart_palette())
+}
+
+pub fn paint(text: &str, color: Option<Color>) -> String {
+    match color {
+        Some(Color(code)) => format!("\x1b[{code}m{text}\x1b[0m"),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_color() {
+        assert!(should_color(None, true));
+        assert!(!should_color(None, false));
+        assert!(!should_color(Some(OsString::from("1")), true));
+        assert!(should_color(Some(OsString::new()), true));
+    }
+
+    #[test]
+    fn test_paint() {
+        assert_eq!(paint("A♥", None), "A♥");
+        assert_eq!(paint("A♥", Some(Color::RED)), "\x1b[31mA♥\x1b[0m");
+    }
+
+    #[test]
+    fn test_themes() {
+        for theme in Theme::ALL {
+            assert_eq!(Theme::parse(theme.name()), Some(theme));
+        }
+        assert_eq!(Theme::parse("Four-Colour"), Some(Theme::FourColor));
+        assert_eq!(Theme::parse("sepia"), None);
+
+        let four = Theme::FourColor.palette();
+        assert_eq!(four.suit(Suit::Clubs), Some(Color::GREEN));
+        assert_eq!(four.suit(Suit::Diamonds), Some(Color::BLUE));
+        assert_eq!(four.suit(Suit::Spades), None);
+
+        // The colour-blind palettes never put red against green, and give every suit its own colour
+        for theme in [Theme::Deuteranopia, Theme::Protanopia, Theme::Tritanopia] {
+            let palette = theme.palette();
+            assert_ne!(palette.win, Some(Color::GREEN));
+            assert_ne!(palette.lose, Some(Color::RED));
+
+            let suits = [Suit::Hearts, Suit::Diamonds, Suit::Clubs, Suit::Spades]
+                .map(|suit| palette.suit(suit));
+            for (i, first) in suits.iter().enumerate() {
+                for second in &suits[i + 1..] {
+                    assert_ne!(first, second, "{theme}");
+                }
+            }
+            assert_ne!(theme.chart_palette().double, Color::ON_GREEN);
+        }
+
+        // Every kind of play has its own colour in the chart
+        for theme in Theme::ALL {
+            let chart = theme.chart_palette();
+            let cells = [
+                chart.hit,
+                chart.stand,
+                chart.double,
+                chart.split,
+                chart.surrender,
+            ];
+            for (i, first) in cells.iter().enumerate() {
+                for second in &cells[i + 1..] {
+                    assert_ne!(first, second, "{theme}");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use serde::{Deserialize, Serialize};
+
+use crate::card::{Card, Rank};
+
+pub fn hi_lo_value(card: &Card) -> i32 {
+    match card.rank {
+        Rank::Two | Rank::Three | Rank::Four | Rank::Five | Rank::Six => 1,
+        Rank::Seven | Rank::Eight | Rank::Nine => 0,
+        Rank::Ten | Rank::Jack | Rank::Queen | Rank::King | Rank::Ace => -1,
+    }
+}
+
+pub fn true_count(running_count: i32, cards_remaining: usize) -> f64 {
+    let decks_remaining = cards_remaining as f64 / 52.0;
+    if decks_remaining <= 0.0 {
+        return running_count as f64;
+    }
+    running_count as f64 / decks_remaining
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunningCount {
+    count: i32,
+    cards_seen: usize,
+}
+
+impl RunningCount {
+    pub fn new() -> Self {
+        RunningCount::default()
+    }
+
+    pub fn observe(&mut self, card: &Card) {
+        self.count += hi_lo_value(card);
+        self.cards_seen += 1;
+    }
+
+    pub fn value(&self) -> i32 {
+        self.count
+    }
+
+    pub fn cards_seen(&self) -> usize {
+        self.cards_seen
+    }
+
+    pub fn true_count(&self, cards_remaining: usize) -> f64 {
+        true_count(self.count, cards_remaining)
+    }
+
+    pub fn reset(&mut self) {
+        self.count = 0;
+        self.cards_seen = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::Suit;
+    use crate::deck::Deck;
+
+    #[test]
+    fn test_hi_lo_values() {
+        let low = Card {
+            suit: Suit::Hearts,
+            rank: Rank::Five,
+        };
+        let neutral = Card {
+            suit: Suit::Clubs,
+            rank: Rank::Eight,
+        };
+        let high = Card {
+            suit: Suit::Spades,
+            rank: Rank::Ace,
+        };
+
+        assert_eq!(hi_lo_value(&low), 1);
+        assert_eq!(hi_lo_value(&neutral), 0);
+        assert_eq!(hi_lo_value(&high), -1);
+    }
+
+    #[test]
+    fn test_full_deck_counts_to_zero() {
+        let mut deck = Deck::new();
+        deck.shuffle();
+
+        let mut count = RunningCount::new();
+        while let Some(card) = deck.deal() {
+            count.observe(&card);
+        }
+
+        assert_eq!(count.value(), 0);
+        assert_eq!(count.cards_seen(), 52);
+    }
+
+    #[test]
+    fn test_true_count() {
+        assert_eq!(true_count(4, 104), 2.0);
+        assert_eq!(true_count(-3, 26), -6.0);
+        // With no cards left the running count is returned unchanged
+        assert_eq!(true_count(5, 0), 5.0);
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut count = RunningCount::new();
+        count.observe(&Card {
+            suit: Suit::Diamonds,
+            rank: Rank::Two,
+        });
+        assert_eq!(count.value(), 1);
+
+        count.reset();
+        assert_eq!(count.value(), 0);
+        assert_eq!(count.cards_seen(), 0);
+    }
+}
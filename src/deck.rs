@@ -1,8 +1,11 @@
 use rand::rng;
 use rand::seq::SliceRandom;
 
+use serde::{Deserialize, Serialize};
+
 use crate::card::{Card, Rank, Suit};
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Deck {
     cards: Vec<Card>,
 }
@@ -15,7 +18,18 @@ impl Default for Deck {
 
 impl Deck {
     pub fn new() -> Self {
+        Deck::with_decks(1)
+    }
+
+    pub fn with_decks(decks: usize) -> Self {
         let mut cards = Vec::new();
+        for _ in 0..decks {
+            Deck::add_deck(&mut cards);
+        }
+        Deck { cards }
+    }
+
+    fn add_deck(cards: &mut Vec<Card>) {
         for suit in [Suit::Hearts, Suit::Diamonds, Suit::Clubs, Suit::Spades] {
             for rank in [
                 Rank::Ace,
@@ -35,13 +49,23 @@ impl Deck {
                 cards.push(Card { suit, rank });
             }
         }
-        Deck { cards }
     }
 
     pub fn shuffle(&mut self) {
         self.cards.shuffle(&mut rng());
     }
 
+    // The same seed always gives the same order, so a shoe can be rebuilt from its seed.
+    // Seeds are saved in the history, so this is a fixed Fisher-Yates over SplitMix64
+    // rather than rand's shuffle, whose output may change between versions
+    pub fn shuffle_seeded(&mut self, seed: u64) {
+        let mut state = seed;
+        for i in (1..self.cards.len()).rev() {
+            let j = ((splitmix64(&mut state) as u128 * (i as u128 + 1)) >> 64) as usize;
+            self.cards.swap(i, j);
+        }
+    }
+
     pub fn deal(&mut self) -> Option<Card> {
         self.cards.pop()
     }
@@ -49,6 +73,35 @@ impl Deck {
     pub fn cards_remaining(&self) -> usize {
         self.cards.len()
     }
+
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    // Cards still to come, in the order they'll be dealt
+    pub fn upcoming(&self) -> impl Iterator<Item = Card> + '_ {
+        self.cards.iter().rev().copied()
+    }
+}
+
+// SplitMix64: each call steps the state and returns a well mixed 64-bit value
+pub(crate) fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+// FNV-1a over the card codes, which unlike std's hasher is the same on every build
+pub fn order_hash(cards: impl IntoIterator<Item = Card>) -> u64 {
+    let mut hash: u64 = 0xCBF2_9CE4_8422_2325;
+    for card in cards {
+        for byte in card.code().bytes() {
+            hash = (hash ^ byte as u64).wrapping_mul(0x0100_0000_01B3);
+        }
+    }
+    hash
 }
 
 #[cfg(test)]
@@ -88,6 +141,15 @@ mod tests {
         assert_eq!(king_count, 4);
     }
 
+    #[test]
+    fn test_multi_deck_shoe() {
+        let shoe = Deck::with_decks(6);
+        assert_eq!(shoe.cards_remaining(), 312);
+
+        let aces = shoe.cards.iter().filter(|c| c.rank == Rank::Ace).count();
+        assert_eq!(aces, 24);
+    }
+
     #[test]
     fn test_deal_reduces_deck_size() {
         let mut deck = Deck::new();
@@ -144,4 +206,30 @@ mod tests {
         // This test might fail very rarely due to randomness, but probability is negligible
         assert_ne!(initial_ranks, shuffled_ranks);
     }
+
+    #[test]
+    fn test_seeded_shuffle_is_repeatable() {
+        let order = |seed| {
+            let mut deck = Deck::with_decks(2);
+            deck.shuffle_seeded(seed);
+            deck.cards
+                .iter()
+                .map(|card| card.code())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(order(42), order(42));
+        assert_ne!(order(42), order(43));
+    }
+
+    #[test]
+    fn test_seeded_shuffle_is_pinned() {
+        // Recorded seeds must rebuild the same shoe on every build, so pin the generator
+        // to SplitMix64's reference output and the first cards dealt from a known seed
+        assert_eq!(splitmix64(&mut 0), 0xE220_A839_7B1D_CDAF);
+
+        let mut deck = Deck::new();
+        deck.shuffle_seeded(42);
+        let dealt: Vec<String> = (0..6).map(|_| deck.deal().unwrap().code()).collect();
+        assert_eq!(dealt, ["KC", "9H", "AD", "4D", "2H", "2S"]);
+    }
 }
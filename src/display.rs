@@ -1,7 +1,111 @@
-use crate::card::{Card, Rank};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
 
-pub fn render_card(card: &Card) -> Vec<String> {
-    let rank_str = match card.rank {
+use crate::analysis::DealerOutcomes;
+use crate::card::{Card, Rank, Suit};
+use crate::color::{self, Color, Palette};
+use crate::outcome::Outcome;
+
+static SUIT_LETTERS: AtomicBool = AtomicBool::new(false);
+static CARD_ART: RwLock<CardArt> = RwLock::new(CardArt::Box);
+
+// Inside the border of a large card
+const LARGE_WIDTH: usize = 9;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CardArt {
+    // A 5x3 box drawn with box-drawing characters
+    #[default]
+    Box,
+    // An 11x9 card with corner indices and pips
+    Large,
+    // The box, drawn with plain ASCII and suit letters
+    Ascii,
+    // One line per card, e.g. [A♠]
+    Compact,
+}
+
+impl CardArt {
+    pub const ALL: [CardArt; 4] = [
+        CardArt::Box,
+        CardArt::Large,
+        CardArt::Ascii,
+        CardArt::Compact,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CardArt::Box => "box",
+            CardArt::Large => "large",
+            CardArt::Ascii => "ascii",
+            CardArt::Compact => "compact",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<CardArt> {
+        let name = name.to_lowercase();
+        CardArt::ALL.into_iter().find(|art| art.name() == name)
+    }
+}
+
+impl fmt::Display for CardArt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+pub fn card_art() -> CardArt {
+    *CARD_ART.read().unwrap_or_else(|err| err.into_inner())
+}
+
+pub fn set_card_art(art: CardArt) {
+    *CARD_ART.write().unwrap_or_else(|err| err.into_inner()) = art;
+}
+
+// Shows suits as H, D, C and S, for anyone who can't tell the symbols apart.
+// ASCII cards always use letters, since the symbols aren't ASCII.
+pub fn set_suit_letters(enabled: bool) {
+    SUIT_LETTERS.store(enabled, Ordering::Relaxed);
+}
+
+pub fn suit_letters() -> bool {
+    SUIT_LETTERS.load(Ordering::Relaxed) || card_art() == CardArt::Ascii
+}
+
+pub fn suit_mark(suit: Suit, letters: bool) -> &'static str {
+    match (suit, letters) {
+        (Suit::Hearts, false) => "♥",
+        (Suit::Diamonds, false) => "♦",
+        (Suit::Clubs, false) => "♣",
+        (Suit::Spades, false) => "♠",
+        (Suit::Hearts, true) => "H",
+        (Suit::Diamonds, true) => "D",
+        (Suit::Clubs, true) => "C",
+        (Suit::Spades, true) => "S",
+    }
+}
+
+// How cards are drawn: the art style, the colour palette, if any, and the suit marks
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CardLook {
+    pub art: CardArt,
+    pub palette: Option<Palette>,
+    pub suit_letters: bool,
+}
+
+impl CardLook {
+    pub fn current() -> CardLook {
+        CardLook {
+            art: card_art(),
+            palette: color::palette(),
+            suit_letters: suit_letters(),
+        }
+    }
+}
+
+fn rank_label(rank: Rank) -> &'static str {
+    match rank {
         Rank::Ace => "A",
         Rank::Two => "2",
         Rank::Three => "3",
@@ -15,30 +119,169 @@ pub fn render_card(card: &Card) -> Vec<String> {
         Rank::Jack => "J",
         Rank::Queen => "Q",
         Rank::King => "K",
-    };
+    }
+}
 
-    let suit_str = match card.suit {
-        crate::card::Suit::Hearts => "♥",
-        crate::card::Suit::Diamonds => "♦",
-        crate::card::Suit::Clubs => "♣",
-        crate::card::Suit::Spades => "♠",
-    };
+// A card in a line of text, e.g. "10♦", or "10D" when suits are shown as letters
+pub fn card_label(card: &Card) -> String {
+    format!(
+        "{}{}",
+        rank_label(card.rank),
+        suit_mark(card.suit, suit_letters())
+    )
+}
+
+pub fn render_card(card: &Card) -> Vec<String> {
+    render_card_with(card, &CardLook::current())
+}
+
+pub fn render_card_with(card: &Card, look: &CardLook) -> Vec<String> {
+    let rank_str = rank_label(card.rank);
+    let letters = look.suit_letters || look.art == CardArt::Ascii;
+    let suit_str = suit_mark(card.suit, letters);
+    let suit_color = look.palette.and_then(|palette| palette.suit(card.suit));
+
+    // Padded before colouring, so the escape codes don't count towards the width
+    let content = format!("{:<3}", format!("{rank_str}{suit_str}"));
+    match look.art {
+        CardArt::Box => vec![
+            "╔═══╗".to_string(),
+            format!("║{}║", color::paint(&content, suit_color)),
+            "╚═══╝".to_string(),
+        ],
+        CardArt::Ascii => vec![
+            "+---+".to_string(),
+            format!("|{}|", color::paint(&content, suit_color)),
+            "+---+".to_string(),
+        ],
+        CardArt::Compact => vec![format!(
+            "[{}]",
+            color::paint(&format!("{rank_str}{suit_str}"), suit_color)
+        )],
+        CardArt::Large => render_large_card(card.rank, rank_str, suit_str, suit_color),
+    }
+}
+
+// Where the pips go in the 5 rows between the corner indices, as (row, column)
+fn pip_positions(rank: Rank) -> &'static [(usize, usize)] {
+    match rank {
+        Rank::Ace => &[(2, 4)],
+        Rank::Two => &[(0, 4), (4, 4)],
+        Rank::Three => &[(0, 4), (2, 4), (4, 4)],
+        Rank::Four => &[(0, 2), (0, 6), (4, 2), (4, 6)],
+        Rank::Five => &[(0, 2), (0, 6), (2, 4), (4, 2), (4, 6)],
+        Rank::Six => &[(0, 2), (0, 6), (2, 2), (2, 6), (4, 2), (4, 6)],
+        Rank::Seven => &[(0, 2), (0, 6), (1, 4), (2, 2), (2, 6), (4, 2), (4, 6)],
+        Rank::Eight => &[
+            (0, 2),
+            (0, 6),
+            (1, 4),
+            (2, 2),
+            (2, 6),
+            (3, 4),
+            (4, 2),
+            (4, 6),
+        ],
+        Rank::Nine => &[
+            (0, 2),
+            (0, 6),
+            (1, 2),
+            (1, 6),
+            (2, 4),
+            (3, 2),
+            (3, 6),
+            (4, 2),
+            (4, 6),
+        ],
+        Rank::Ten => &[
+            (0, 2),
+            (0, 6),
+            (1, 2),
+            (1, 4),
+            (1, 6),
+            (3, 2),
+            (3, 4),
+            (3, 6),
+            (4, 2),
+            (4, 6),
+        ],
+        Rank::Jack | Rank::Queen | Rank::King => &[],
+    }
+}
 
-    let content = format!("{rank_str}{suit_str}");
+fn render_large_card(
+    rank: Rank,
+    rank_str: &str,
+    suit_str: &str,
+    suit_color: Option<Color>,
+) -> Vec<String> {
+    let index = format!("{rank_str}{suit_str}");
+    let middle: Vec<String> = match rank {
+        // Court cards get a framed letter instead of pips
+        Rank::Jack | Rank::Queen | Rank::King => vec![
+            "  ╭───╮  ".to_string(),
+            format!("  │ {suit_str} │  "),
+            format!("  │ {rank_str} │  "),
+            format!("  │ {suit_str} │  "),
+            "  ╰───╯  ".to_string(),
+        ],
+        _ => {
+            let mut rows = vec![vec![" "; LARGE_WIDTH]; 5];
+            for &(row, column) in pip_positions(rank) {
+                rows[row][column] = suit_str;
+            }
+            rows.into_iter().map(|row| row.concat()).collect()
+        }
+    };
 
-    vec![
-        "╔═══╗".to_string(),
-        format!("║{:<3}║", content),
-        "╚═══╝".to_string(),
-    ]
+    let mut lines = vec![format!("┌{}┐", "─".repeat(LARGE_WIDTH))];
+    let interior = [format!("{index:<LARGE_WIDTH$}")]
+        .into_iter()
+        .chain(middle)
+        .chain([format!("{index:>LARGE_WIDTH$}")]);
+    for row in interior {
+        lines.push(format!("│{}│", color::paint(&row, suit_color)));
+    }
+    lines.push(format!("└{}┘", "─".repeat(LARGE_WIDTH)));
+    lines
 }
 
 pub fn render_hidden_card() -> Vec<String> {
-    vec![
-        "╔═══╗".to_string(),
-        "║░░░║".to_string(),
-        "╚═══╝".to_string(),
-    ]
+    render_hidden_card_with(&CardLook::current())
+}
+
+pub fn render_hidden_card_with(look: &CardLook) -> Vec<String> {
+    let back = look.palette.and_then(|palette| palette.back);
+    let lines = match look.art {
+        CardArt::Box => vec![
+            "╔═══╗".to_string(),
+            "║░░░║".to_string(),
+            "╚═══╝".to_string(),
+        ],
+        CardArt::Ascii => vec![
+            "+---+".to_string(),
+            "|###|".to_string(),
+            "+---+".to_string(),
+        ],
+        CardArt::Compact => vec!["[??]".to_string()],
+        CardArt::Large => {
+            let mut lines = vec![format!("┌{}┐", "─".repeat(LARGE_WIDTH))];
+            lines.extend((0..7).map(|_| format!("│{}│", "░".repeat(LARGE_WIDTH))));
+            lines.push(format!("└{}┘", "─".repeat(LARGE_WIDTH)));
+            lines
+        }
+    };
+    lines.iter().map(|line| color::paint(line, back)).collect()
+}
+
+// Wins, losses and pushes each in the theme's colour
+pub fn render_outcome(outcome: Outcome) -> String {
+    let color = color::palette().and_then(|palette| match outcome {
+        Outcome::DealerBust | Outcome::PlayerBlackjack | Outcome::PlayerWins => palette.win,
+        Outcome::PlayerBust | Outcome::DealerBlackjack | Outcome::DealerWins => palette.lose,
+        Outcome::Push => palette.push,
+    });
+    color::paint(outcome.message(), color)
 }
 
 pub fn render_cards_horizontal(cards: &[Vec<String>]) -> String {
@@ -46,7 +289,7 @@ pub fn render_cards_horizontal(cards: &[Vec<String>]) -> String {
         return String::new();
     }
 
-    let height = cards[0].len();
+    let height = cards.iter().map(Vec::len).max().unwrap_or(0);
     let mut result = Vec::new();
 
     for row in 0..height {
@@ -55,7 +298,13 @@ pub fn render_cards_horizontal(cards: &[Vec<String>]) -> String {
             if i > 0 {
                 line.push(' ');
             }
-            line.push_str(&card[row]);
+            match card.get(row) {
+                Some(text) => line.push_str(text),
+                // A shorter card leaves a gap as wide as itself so later cards stay aligned
+                None => {
+                    line.push_str(&" ".repeat(card.first().map_or(0, |text| visible_width(text))))
+                }
+            }
         }
         result.push(line);
     }
@@ -63,10 +312,46 @@ pub fn render_cards_horizontal(cards: &[Vec<String>]) -> String {
     result.join("\n")
 }
 
+// Counts the characters a line shows, skipping colour escape sequences
+fn visible_width(line: &str) -> usize {
+    let mut width = 0;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            width += 1;
+        }
+    }
+    width
+}
+
+pub fn render_odds(bust_chance: f64, upcard: &Card, dealer: &DealerOutcomes) -> String {
+    let mut lines = vec!["=== Odds ===".to_string()];
+    lines.push(format!("Bust if you hit: {:.1}%", bust_chance * 100.0));
+    lines.push(format!(
+        "Dealer showing {} finishes with:",
+        card_label(upcard)
+    ));
+
+    let mut outcomes: Vec<String> = (17..=21)
+        .map(|total| format!("{total}: {:.1}%", dealer.total(total) * 100.0))
+        .collect();
+    outcomes.push(format!("Blackjack: {:.1}%", dealer.blackjack * 100.0));
+    outcomes.push(format!("Bust: {:.1}%", dealer.bust * 100.0));
+    lines.push(format!("  {}", outcomes.join("  ")));
+
+    lines.join("\n")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::card::{Card, Rank, Suit};
+    use crate::color::Theme;
 
     #[test]
     fn test_render_card() {
@@ -98,6 +383,182 @@ mod tests {
         assert_eq!(rendered[2], "╚═══╝");
     }
 
+    #[test]
+    fn test_render_card_colors() {
+        let heart = Card {
+            suit: Suit::Hearts,
+            rank: Rank::Ace,
+        };
+        let club = Card {
+            suit: Suit::Clubs,
+            rank: Rank::Ten,
+        };
+        let classic = CardLook {
+            palette: Some(Theme::Classic.palette()),
+            ..CardLook::default()
+        };
+        assert_eq!(
+            render_card_with(&heart, &classic)[1],
+            "║\x1b[31mA♥ \x1b[0m║"
+        );
+        assert_eq!(render_card_with(&club, &classic)[1], "║10♣║");
+        assert_eq!(
+            render_card_with(&heart, &CardLook::default()),
+            render_card(&heart)
+        );
+        assert!(render_hidden_card_with(&classic)[1].starts_with("\x1b[34m"));
+
+        let four_color = CardLook {
+            palette: Some(Theme::FourColor.palette()),
+            ..CardLook::default()
+        };
+        assert_eq!(
+            render_card_with(&club, &four_color)[1],
+            "║\x1b[32m10♣\x1b[0m║"
+        );
+
+        // Colouring doesn't change how the cards line up
+        let result = render_cards_horizontal(&[
+            render_card_with(&heart, &classic),
+            render_hidden_card_with(&classic),
+        ]);
+        assert_eq!(result.lines().count(), 3);
+    }
+
+    #[test]
+    fn test_suit_letters() {
+        let letters = CardLook {
+            suit_letters: true,
+            ..CardLook::default()
+        };
+        let diamond = Card {
+            suit: Suit::Diamonds,
+            rank: Rank::Ten,
+        };
+        assert_eq!(render_card_with(&diamond, &letters)[1], "║10D║");
+        assert_eq!(suit_mark(Suit::Hearts, true), "H");
+        assert_eq!(suit_mark(Suit::Hearts, false), "♥");
+    }
+
+    fn look(art: CardArt) -> CardLook {
+        CardLook {
+            art,
+            ..CardLook::default()
+        }
+    }
+
+    #[test]
+    fn test_large_cards() {
+        let seven = Card {
+            suit: Suit::Spades,
+            rank: Rank::Seven,
+        };
+        let ten = Card {
+            suit: Suit::Hearts,
+            rank: Rank::Ten,
+        };
+        let large = look(CardArt::Large);
+        let rendered = render_card_with(&seven, &large);
+        assert_eq!(rendered.len(), 9);
+        assert_eq!(rendered[0], "┌─────────┐");
+        assert_eq!(rendered[1], "│7♠       │");
+        assert_eq!(rendered[7], "│       7♠│");
+        // Seven pips plus the two corner indices
+        assert_eq!(rendered.concat().matches('♠').count(), 9);
+        assert!(rendered
+            .iter()
+            .all(|line| line.chars().count() == LARGE_WIDTH + 2));
+
+        let rendered = render_card_with(&ten, &large);
+        assert_eq!(rendered.concat().matches('♥').count(), 12);
+        assert_eq!(rendered[1], "│10♥      │");
+
+        let king = Card {
+            suit: Suit::Clubs,
+            rank: Rank::King,
+        };
+        assert!(render_card_with(&king, &large)[4].contains("│ K │"));
+        assert_eq!(render_hidden_card_with(&large).len(), 9);
+    }
+
+    #[test]
+    fn test_ascii_and_compact_cards() {
+        let ten = Card {
+            suit: Suit::Diamonds,
+            rank: Rank::Ten,
+        };
+        let ascii = render_card_with(&ten, &look(CardArt::Ascii));
+        assert_eq!(ascii, vec!["+---+", "|10D|", "+---+"]);
+        assert!(ascii.concat().is_ascii());
+        assert_eq!(render_hidden_card_with(&look(CardArt::Ascii))[1], "|###|");
+
+        let compact = look(CardArt::Compact);
+        assert_eq!(render_card_with(&ten, &compact), vec!["[10♦]"]);
+        assert_eq!(render_hidden_card_with(&compact), vec!["[??]"]);
+    }
+
+    #[test]
+    fn test_horizontal_with_every_art() {
+        let cards = [
+            Card {
+                suit: Suit::Spades,
+                rank: Rank::Ace,
+            },
+            Card {
+                suit: Suit::Hearts,
+                rank: Rank::Ten,
+            },
+        ];
+        for art in CardArt::ALL {
+            let look = look(art);
+            let mut rendered = vec![render_hidden_card_with(&look)];
+            rendered.extend(cards.iter().map(|card| render_card_with(card, &look)));
+            let result = render_cards_horizontal(&rendered);
+            assert_eq!(result.lines().count(), rendered[0].len(), "{art}");
+
+            // Every row is the same width, so the cards line up
+            let widths: Vec<usize> = result.lines().map(|line| line.chars().count()).collect();
+            assert!(widths.iter().all(|&width| width == widths[0]), "{art}");
+        }
+
+        let compact: Vec<Vec<String>> = cards
+            .iter()
+            .map(|card| render_card_with(card, &look(CardArt::Compact)))
+            .collect();
+        assert_eq!(render_cards_horizontal(&compact), "[A♠] [10♥]");
+
+        // A short card beside a tall one is padded so the rows still line up
+        let mixed = [
+            render_card_with(&cards[0], &look(CardArt::Box)),
+            render_card_with(&cards[1], &look(CardArt::Large)),
+        ];
+        assert!(mixed[0].len() < mixed[1].len());
+        let result = render_cards_horizontal(&mixed);
+        let widths: Vec<usize> = result.lines().map(visible_width).collect();
+        assert!(widths.iter().all(|&width| width == widths[0]), "{widths:?}");
+        assert_eq!(CardArt::parse("ASCII"), Some(CardArt::Ascii));
+    }
+
+    #[test]
+    fn test_render_odds() {
+        let dealer = DealerOutcomes {
+            totals: [0.1, 0.1, 0.1, 0.2, 0.1],
+            blackjack: 0.05,
+            bust: 0.35,
+        };
+        let upcard = Card {
+            suit: Suit::Hearts,
+            rank: Rank::Ten,
+        };
+
+        let panel = render_odds(0.385, &upcard, &dealer);
+        assert!(panel.contains("Bust if you hit: 38.5%"));
+        assert!(panel.contains("Dealer showing 10♥"));
+        assert!(panel.contains("20: 20.0%"));
+        assert!(panel.contains("Blackjack: 5.0%"));
+        assert!(panel.contains("Bust: 35.0%"));
+    }
+
     #[test]
     fn test_render_cards_horizontal() {
         let card1 = render_card(&Card {
@@ -0,0 +1,253 @@
+use std::io::{self, Write};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::count::RunningCount;
+use crate::deck::Deck;
+use crate::display::{render_card, render_cards_horizontal};
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DrillStats {
+    pub attempts: u32,
+    pub correct: u32,
+    pub total_time: Duration,
+}
+
+impl DrillStats {
+    pub fn new() -> Self {
+        DrillStats::default()
+    }
+
+    pub fn record(&mut self, correct: bool, elapsed: Duration) {
+        self.attempts += 1;
+        if correct {
+            self.correct += 1;
+        }
+        self.total_time += elapsed;
+    }
+
+    pub fn accuracy(&self) -> f64 {
+        if self.attempts == 0 {
+            return 0.0;
+        }
+        self.correct as f64 * 100.0 / self.attempts as f64
+    }
+
+    pub fn average_time(&self) -> Duration {
+        if self.attempts == 0 {
+            return Duration::ZERO;
+        }
+        self.total_time / self.attempts
+    }
+
+    pub fn print_summary(&self, name: &str) {
+        println!("=== {name} ===");
+        println!(
+            "Correct: {}/{} ({:.1}%)",
+            self.correct,
+            self.attempts,
+            self.accuracy()
+        );
+        println!(
+            "Total time: {:.1}s, average {:.1}s per attempt",
+            self.total_time.as_secs_f64(),
+            self.average_time().as_secs_f64()
+        );
+    }
+}
+
+pub struct CountdownOptions {
+    pub group_size: usize,
+    pub delay: Duration,
+}
+
+impl Default for CountdownOptions {
+    fn default() -> Self {
+        CountdownOptions {
+            group_size: 1,
+            delay: Duration::from_millis(1000),
+        }
+    }
+}
+
+impl CountdownOptions {
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let mut options = CountdownOptions::default();
+        let mut iter = args.iter();
+
+        while let Some(arg) = iter.next() {
+            let value = iter
+                .next()
+                .ok_or_else(|| format!("Missing value for {arg}"))?;
+            match arg.as_str() {
+                "--group" => match value.parse::<usize>() {
+                    Ok(size @ 1..=3) => options.group_size = size,
+                    _ => return Err("Group size must be 1, 2 or 3".to_string()),
+                },
+                "--speed" => match value.parse::<u64>() {
+                    Ok(ms) => options.delay = Duration::from_millis(ms),
+                    Err(_) => return Err("Speed must be a number of milliseconds".to_string()),
+                },
+                _ => return Err(format!("Unknown option: {arg}")),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+pub fn is_true_count_close(answer: f64, actual: f64) -> bool {
+    (answer - actual).abs() <= 0.5
+}
+
+fn prompt(message: &str) -> String {
+    print!("{message}");
+    io::stdout().flush().unwrap();
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).unwrap();
+    input.trim().to_string()
+}
+
+fn prompt_number<T: std::str::FromStr>(message: &str) -> T {
+    loop {
+        match prompt(message).parse::<T>() {
+            Ok(value) => return value,
+            Err(_) => println!("Invalid input! Please enter a number."),
+        }
+    }
+}
+
+fn clear_screen() {
+    print!("\x1b[2J\x1b[H");
+    io::stdout().flush().unwrap();
+}
+
+fn countdown_round(options: &CountdownOptions) -> (bool, Duration) {
+    let mut deck = Deck::new();
+    deck.shuffle();
+    let mut count = RunningCount::new();
+
+    let start = Instant::now();
+    loop {
+        let mut group = Vec::new();
+        for _ in 0..options.group_size {
+            if let Some(card) = deck.deal() {
+                count.observe(&card);
+                group.push(render_card(&card));
+            }
+        }
+        if group.is_empty() {
+            break;
+        }
+
+        clear_screen();
+        println!("{}", render_cards_horizontal(&group));
+        thread::sleep(options.delay);
+    }
+    clear_screen();
+
+    let answer: i32 = prompt_number("What is the running count? ");
+    let elapsed = start.elapsed();
+    let correct = answer == count.value();
+
+    if correct {
+        println!("Correct!");
+    } else {
+        println!("Wrong! The running count was {}.", count.value());
+    }
+
+    (correct, elapsed)
+}
+
+pub fn run_countdown(options: &CountdownOptions) -> DrillStats {
+    let mut stats = DrillStats::new();
+
+    println!("Deck countdown: count every card, then enter the running count.");
+    loop {
+        prompt("Press Enter to start...");
+        let (correct, elapsed) = countdown_round(options);
+        stats.record(correct, elapsed);
+
+        match prompt("Another deck? (y/n) ").to_lowercase().as_str() {
+            "y" | "yes" => continue,
+            _ => break,
+        }
+    }
+
+    stats.print_summary("Deck countdown");
+    stats
+}
+
+pub fn count_check(running_count: i32, true_count: f64) -> (bool, Duration) {
+    println!("\n*** Count check! ***");
+    let start = Instant::now();
+    let running_answer: i32 = prompt_number("Running count? ");
+    let true_answer: f64 = prompt_number("True count? ");
+    let elapsed = start.elapsed();
+
+    let running_correct = running_answer == running_count;
+    let true_correct = is_true_count_close(true_answer, true_count);
+
+    if running_correct && true_correct {
+        println!("Correct!");
+    } else {
+        println!(
+            "The running count is {} and the true count is {:.1}.",
+            running_count, true_count
+        );
+    }
+    println!();
+
+    (running_correct && true_correct, elapsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_drill_stats() {
+        let mut stats = DrillStats::new();
+        assert_eq!(stats.accuracy(), 0.0);
+        assert_eq!(stats.average_time(), Duration::ZERO);
+
+        stats.record(true, Duration::from_secs(4));
+        stats.record(false, Duration::from_secs(2));
+
+        assert_eq!(stats.attempts, 2);
+        assert_eq!(stats.correct, 1);
+        assert_eq!(stats.accuracy(), 50.0);
+        assert_eq!(stats.average_time(), Duration::from_secs(3));
+    }
+
+    #[test]
+    fn test_countdown_options_from_args() {
+        let options = CountdownOptions::from_args(&args(&["--group", "2", "--speed", "250"]))
+            .expect("valid options");
+        assert_eq!(options.group_size, 2);
+        assert_eq!(options.delay, Duration::from_millis(250));
+
+        let defaults = CountdownOptions::from_args(&[]).expect("no options");
+        assert_eq!(defaults.group_size, 1);
+    }
+
+    #[test]
+    fn test_countdown_options_rejects_bad_input() {
+        assert!(CountdownOptions::from_args(&args(&["--group", "4"])).is_err());
+        assert!(CountdownOptions::from_args(&args(&["--speed", "fast"])).is_err());
+        assert!(CountdownOptions::from_args(&args(&["--group"])).is_err());
+        assert!(CountdownOptions::from_args(&args(&["--colour", "red"])).is_err());
+    }
+
+    #[test]
+    fn test_true_count_tolerance() {
+        assert!(is_true_count_close(2.0, 2.4));
+        assert!(is_true_count_close(-1.0, -1.5));
+        assert!(!is_true_count_close(3.0, 2.2));
+    }
+}
@@ -0,0 +1,385 @@
+use std::fmt;
+
+use crate::analysis::ShoeComposition;
+use crate::card::{Card, Rank, Suit};
+use crate::ev::{self, EvCalculator};
+use crate::fastsim::{self, FastConfig};
+use crate::hand::Hand;
+use crate::rules::Rules;
+use crate::sim::{PlayerStrategy, SimStats};
+
+// Two-sided 95% confidence
+const Z_95: f64 = 1.96;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleEffect {
+    pub rule: String,
+    pub effect: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct HouseEdge {
+    pub rules: Rules,
+    // From every upcard and starting hand, playing each hand's best action and
+    // splitting at most once, as the EV calculator does
+    pub computed: f64,
+    // Resplitting, which the calculator doesn't play out, from published figures
+    pub table_estimates: Vec<RuleEffect>,
+    // What each rule adds, found by switching it back to a single deck game's and recomputing
+    pub effects: Vec<RuleEffect>,
+    pub simulated: Option<SimStats>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct EdgeOptions {
+    pub rules: Rules,
+    // Hands to play out with the fast simulator as a check on the calculation
+    pub simulate: Option<u64>,
+}
+
+impl EdgeOptions {
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let mut options = EdgeOptions::default();
+        let mut iter = args.iter();
+
+        while let Some(arg) = iter.next() {
+            if options.rules.apply_option(arg, &mut iter)? {
+                continue;
+            }
+
+            let value = iter
+                .next()
+                .ok_or_else(|| format!("Missing value for {arg}"))?;
+            match arg.as_str() {
+                "--simulate" => {
+                    options.simulate = match value.replace(['_', ','], "").parse::<u64>() {
+                        Ok(hands) if hands > 0 => Some(hands),
+                        _ => return Err(format!("Invalid number of hands: {value}")),
+                    }
+                }
+                _ => return Err(format!("Unknown option: {arg}")),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+impl HouseEdge {
+    pub fn estimate(rules: &Rules) -> HouseEdge {
+        let computed = computed_edge(rules);
+        let single_deck = rules.decks == 1;
+
+        let mut effects = Vec::new();
+        let mut toggle = |rule: String, baseline: Rules| {
+            effects.push(RuleEffect {
+                rule,
+                effect: computed - computed_edge(&baseline),
+            });
+        };
+        if rules.decks != 1 {
+            toggle(
+                format!("{} decks", rules.decks),
+                Rules { decks: 1, ..*rules },
+            );
+        }
+        if rules.dealer_hits_soft_17 {
+            toggle(
+                "Dealer hits soft 17".to_string(),
+                Rules {
+                    dealer_hits_soft_17: false,
+                    ..*rules
+                },
+            );
+        }
+        if rules.double_after_split && rules.max_split_hands > 1 {
+            toggle(
+                "Double after split".to_string(),
+                Rules {
+                    double_after_split: false,
+                    ..*rules
+                },
+            );
+        }
+        if rules.surrender {
+            toggle(
+                "Late surrender".to_string(),
+                Rules {
+                    surrender: false,
+                    ..*rules
+                },
+            );
+        }
+        if rules.max_split_hands == 1 {
+            toggle(
+                "No splitting".to_string(),
+                Rules {
+                    max_split_hands: 4,
+                    ..*rules
+                },
+            );
+        }
+        if !rules.dealer_peeks {
+            toggle(
+                "No hole card".to_string(),
+                Rules {
+                    dealer_peeks: true,
+                    ..*rules
+                },
+            );
+        }
+        if rules.blackjack_payout != (3, 2) {
+            let (numerator, denominator) = rules.blackjack_payout;
+            toggle(
+                format!("Blackjack pays {numerator}:{denominator}"),
+                Rules {
+                    blackjack_payout: (3, 2),
+                    ..*rules
+                },
+            );
+        }
+
+        let mut table_estimates = Vec::new();
+        match rules.max_split_hands {
+            3 => table_estimates.push(RuleEffect {
+                rule: "Resplit to 3 hands".to_string(),
+                effect: -0.0004,
+            }),
+            4 => table_estimates.push(RuleEffect {
+                rule: "Resplit to 4 hands".to_string(),
+                effect: -0.0005,
+            }),
+            _ => {}
+        }
+        if rules.resplit_aces && rules.max_split_hands > 2 {
+            table_estimates.push(RuleEffect {
+                rule: "Resplit aces".to_string(),
+                effect: if single_deck { -0.0003 } else { -0.0008 },
+            });
+        }
+
+        HouseEdge {
+            rules: *rules,
+            computed,
+            table_estimates,
+            effects,
+            simulated: None,
+        }
+    }
+
+    // Plays the rules out with the fast simulator as a check on the calculation
+    pub fn with_simulation(mut self, hands: u64, seed: u64) -> Self {
+        self.simulated = Some(fastsim::simulate(&FastConfig {
+            rules: self.rules,
+            player: PlayerStrategy::Basic,
+            hands,
+            seed,
+            threads: fastsim::available_threads(),
+            ramp: None,
+        }));
+        self
+    }
+
+    pub fn total(&self) -> f64 {
+        self.computed
+            + self
+                .table_estimates
+                .iter()
+                .map(|effect| effect.effect)
+                .sum::<f64>()
+    }
+}
+
+// The house edge is the player's expected loss per unit of the original bet
+pub fn computed_edge(rules: &Rules) -> f64 {
+    let full = ShoeComposition::full(rules.decks);
+    let mut value = 0.0;
+
+    for upcard in 2..=11 {
+        let mut calculator = EvCalculator::new(*rules, upcard);
+        let p_upcard = full.probability(upcard);
+        let after_upcard = full.without_value(upcard);
+
+        for first in 2..=11 {
+            let p_first = after_upcard.probability(first);
+            if p_first == 0.0 {
+                continue;
+            }
+            let after_first = after_upcard.without_value(first);
+
+            // Unpaired hands are counted once for both orders they can be dealt in
+            for second in first..=11 {
+                let orders = if second == first { 1.0 } else { 2.0 };
+                let p_second = orders * after_first.probability(second);
+                if p_second == 0.0 {
+                    continue;
+                }
+                let shoe = after_first.without_value(second);
+                let hand_value =
+                    starting_hand_value(&mut calculator, rules, upcard, [first, second], &shoe);
+                value += p_upcard * p_first * p_second * hand_value;
+            }
+        }
+    }
+
+    -value
+}
+
+fn starting_hand_value(
+    calculator: &mut EvCalculator,
+    rules: &Rules,
+    upcard: u8,
+    cards: [u8; 2],
+    shoe: &ShoeComposition,
+) -> f64 {
+    let mut hand = Hand::new();
+    for value in cards {
+        hand.add_card(card_with_value(value));
+    }
+    let values = calculator.action_values(&hand, shoe, ev::available_for(&hand, rules));
+    let played = if hand.is_blackjack() {
+        values.stand
+    } else {
+        values.best().1
+    };
+    if !rules.dealer_peeks {
+        // The calculator already counts a dealer blackjack against every bet on the table
+        return played;
+    }
+
+    // The dealer checks first, and only a player blackjack survives a dealer blackjack
+    let dealer_blackjack = match upcard {
+        11 => shoe.probability(10),
+        10 => shoe.probability(11),
+        _ => 0.0,
+    };
+    let against_blackjack = if hand.is_blackjack() { 0.0 } else { -1.0 };
+    dealer_blackjack * against_blackjack + (1.0 - dealer_blackjack) * played
+}
+
+fn card_with_value(value: u8) -> Card {
+    let rank = match value {
+        11 => Rank::Ace,
+        10 => Rank::Ten,
+        value => Rank::from_symbol(&value.to_string()).expect("values 2-9 are ranks"),
+    };
+    Card {
+        suit: Suit::Spades,
+        rank,
+    }
+}
+
+impl fmt::Display for HouseEdge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "House edge for {}\n", self.rules)?;
+        writeln!(
+            f,
+            "{:<36} {:+.2}%",
+            "Computed, splitting once",
+            self.computed * 100.0
+        )?;
+        for effect in &self.table_estimates {
+            let rule = format!("{} (table estimate)", effect.rule);
+            writeln!(f, "{:<36} {:+.2}%", rule, effect.effect * 100.0)?;
+        }
+        writeln!(f, "{}", "-".repeat(44))?;
+        write!(f, "{:<36} {:+.2}%", "House edge", self.total() * 100.0)?;
+
+        if let Some(stats) = &self.simulated {
+            // The simulator reports the player's result, so flip it to the house's side
+            let (low, high) = stats.confidence_interval(Z_95);
+            write!(
+                f,
+                "\n{:<36} {:+.2}% (95%: {:+.2}% to {:+.2}%)",
+                format!("Simulated over {} hands", stats.hands),
+                -stats.expected_value() * 100.0,
+                -high * 100.0,
+                -low * 100.0
+            )?;
+        }
+
+        if !self.effects.is_empty() {
+            write!(
+                f,
+                "\n\nEach rule's effect, recomputed with that rule as in a single deck game:"
+            )?;
+            for effect in &self.effects {
+                write!(f, "\n{:<36} {:+.2}%", effect.rule, effect.effect * 100.0)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Single deck keeps the calculation quick enough for tests
+    fn single_deck() -> Rules {
+        Rules {
+            decks: 1,
+            ..Rules::default()
+        }
+    }
+
+    #[test]
+    fn test_single_deck_edge() {
+        // Single deck, S17, DAS and late surrender at 3:2 is a small edge for the player
+        let edge = HouseEdge::estimate(&single_deck()).with_simulation(200_000, 5);
+        assert!((-0.003..-0.001).contains(&edge.total()), "{}", edge.total());
+
+        let rules: Vec<&str> = edge.effects.iter().map(|e| e.rule.as_str()).collect();
+        assert_eq!(rules, ["Double after split", "Late surrender"]);
+        assert!(edge.effects.iter().all(|effect| effect.effect < 0.0));
+        assert_eq!(edge.table_estimates.len(), 1);
+
+        // The simulator plays the same rules out; the run is seeded so the noise is fixed
+        let stats = edge.simulated.as_ref().unwrap();
+        assert!((-stats.expected_value() - edge.total()).abs() < 0.01);
+
+        let text = edge.to_string();
+        assert!(text.contains("Resplit to 4 hands (table estimate)"));
+        assert!(text.contains("Simulated over 200000 hands"));
+    }
+
+    #[test]
+    fn test_edge_options_from_args() {
+        let args: Vec<String> = ["--preset", "european", "--simulate", "1_000_000"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        let options = EdgeOptions::from_args(&args).unwrap();
+        assert!(!options.rules.dealer_peeks);
+        assert_eq!(options.simulate, Some(1_000_000));
+
+        assert!(EdgeOptions::from_args(&["--simulate".to_string()]).is_err());
+        assert!(EdgeOptions::from_args(&["--bogus".to_string(), "1".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_rules_that_hurt_the_player() {
+        let base = computed_edge(&single_deck());
+
+        let six_five = Rules {
+            blackjack_payout: (6, 5),
+            ..single_deck()
+        };
+        // A 6:5 payout costs the player well over a percent
+        let difference = computed_edge(&six_five) - base;
+        assert!((0.012..0.016).contains(&difference), "{difference}");
+
+        let h17 = Rules {
+            dealer_hits_soft_17: true,
+            ..single_deck()
+        };
+        let difference = computed_edge(&h17) - base;
+        assert!((0.0015..0.003).contains(&difference), "{difference}");
+
+        let no_hole_card = Rules {
+            dealer_peeks: false,
+            ..single_deck()
+        };
+        assert!(computed_edge(&no_hole_card) > base);
+    }
+}
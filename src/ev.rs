@@ -0,0 +1,387 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::analysis::{self, DealerGraph, DealerOutcomes, ShoeComposition};
+use crate::card::{Card, Rank};
+use crate::hand::Hand;
+use crate::rules::Rules;
+use crate::strategy::{Action, Available};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ActionValues {
+    pub stand: f64,
+    pub hit: f64,
+    pub double: Option<f64>,
+    pub split: Option<f64>,
+    pub surrender: Option<f64>,
+}
+
+impl ActionValues {
+    pub fn value(&self, action: Action) -> Option<f64> {
+        match action {
+            Action::Stand => Some(self.stand),
+            Action::Hit => Some(self.hit),
+            Action::Double => self.double,
+            Action::Split => self.split,
+            Action::Surrender => self.surrender,
+        }
+    }
+
+    pub fn best(&self) -> (Action, f64) {
+        let mut best = (Action::Stand, self.stand);
+        for action in [
+            Action::Hit,
+            Action::Double,
+            Action::Split,
+            Action::Surrender,
+        ] {
+            if let Some(value) = self.value(action) {
+                if value > best.1 {
+                    best = (action, value);
+                }
+            }
+        }
+        best
+    }
+
+    // How much expected value choosing this action gives up against the best one
+    pub fn cost(&self, action: Action) -> Option<f64> {
+        Some(self.best().1 - self.value(action)?)
+    }
+}
+
+impl fmt::Display for ActionValues {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (best, _) = self.best();
+        for action in [
+            Action::Stand,
+            Action::Hit,
+            Action::Double,
+            Action::Split,
+            Action::Surrender,
+        ] {
+            if let Some(value) = self.value(action) {
+                let marker = if action == best { "  <- best" } else { "" };
+                writeln!(f, "{:<10} {:+.4}{marker}", action.to_string(), value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// Expected values are per unit of the original bet. Player draws are taken from
+// the shoe before the dealer's hole card, and when the dealer peeks the dealer's
+// outcomes are conditioned on not having blackjack. Split hands are not resplit.
+pub struct EvCalculator {
+    rules: Rules,
+    dealer_graph: DealerGraph,
+    dealer_cache: HashMap<ShoeComposition, DealerOutcomes>,
+    hit_cache: HashMap<(ShoeComposition, u8, u8), f64>,
+}
+
+impl EvCalculator {
+    pub fn new(rules: Rules, upcard: u8) -> Self {
+        EvCalculator {
+            rules,
+            dealer_graph: DealerGraph::new(upcard, &rules),
+            dealer_cache: HashMap::new(),
+            hit_cache: HashMap::new(),
+        }
+    }
+
+    pub fn dealer(&mut self, shoe: &ShoeComposition) -> DealerOutcomes {
+        if let Some(outcomes) = self.dealer_cache.get(shoe) {
+            return *outcomes;
+        }
+        let mut outcomes = self.dealer_graph.outcomes(shoe);
+        if self.rules.dealer_peeks {
+            outcomes = outcomes.given_no_blackjack();
+        }
+        self.dealer_cache.insert(*shoe, outcomes);
+        outcomes
+    }
+
+    pub fn stand(&mut self, total: u8, shoe: &ShoeComposition) -> f64 {
+        if total > 21 {
+            return -1.0;
+        }
+        let dealer = self.dealer(shoe);
+
+        let mut value = dealer.bust - dealer.blackjack;
+        for dealer_total in 17..=21 {
+            let p = dealer.total(dealer_total);
+            if total > dealer_total {
+                value += p;
+            } else if total < dealer_total {
+                value -= p;
+            }
+        }
+        value
+    }
+
+    fn stand_or_hit(&mut self, total: u8, soft_aces: u8, shoe: &ShoeComposition) -> f64 {
+        if total > 21 {
+            return -1.0;
+        }
+        let stand = self.stand(total, shoe);
+        if total == 21 {
+            return stand;
+        }
+        stand.max(self.hit(total, soft_aces, shoe))
+    }
+
+    pub fn hit(&mut self, total: u8, soft_aces: u8, shoe: &ShoeComposition) -> f64 {
+        let key = (*shoe, total, soft_aces);
+        if let Some(value) = self.hit_cache.get(&key) {
+            return *value;
+        }
+
+        let mut value = 0.0;
+        let mut next = *shoe;
+        for card in 2..=11 {
+            let p = shoe.probability(card);
+            if p == 0.0 {
+                continue;
+            }
+            next.remove(card);
+            let (total, soft_aces) = analysis::add_card(total, soft_aces, card);
+            value += p * self.stand_or_hit(total, soft_aces, &next);
+            next.add(card);
+        }
+
+        self.hit_cache.insert(key, value);
+        value
+    }
+
+    pub fn double(&mut self, total: u8, soft_aces: u8, shoe: &ShoeComposition) -> f64 {
+        let mut value = 0.0;
+        let mut next = *shoe;
+        for card in 2..=11 {
+            let p = shoe.probability(card);
+            if p == 0.0 {
+                continue;
+            }
+            next.remove(card);
+            let (total, _) = analysis::add_card(total, soft_aces, card);
+            value += p * 2.0 * self.stand(total, &next);
+            next.add(card);
+        }
+        value
+    }
+
+    // Both split hands are valued as if played from the same shoe
+    pub fn split(&mut self, card: u8, shoe: &ShoeComposition) -> f64 {
+        let mut value = 0.0;
+        let mut next = *shoe;
+        for drawn in 2..=11 {
+            let p = shoe.probability(drawn);
+            if p == 0.0 {
+                continue;
+            }
+            next.remove(drawn);
+            let (start_total, start_soft) = analysis::add_card(0, 0, card);
+            let (total, soft_aces) = analysis::add_card(start_total, start_soft, drawn);
+
+            let hand_value = if card == 11 {
+                // Split aces take one card each
+                self.stand(total, &next)
+            } else {
+                let mut best = self.stand_or_hit(total, soft_aces, &next);
+                if self.rules.double_after_split {
+                    best = best.max(self.double(total, soft_aces, &next));
+                }
+                best
+            };
+            value += p * hand_value;
+            next.add(drawn);
+        }
+        2.0 * value
+    }
+
+    pub fn action_values(
+        &mut self,
+        hand: &Hand,
+        shoe: &ShoeComposition,
+        available: Available,
+    ) -> ActionValues {
+        let (total, soft_aces) = hand.cards().iter().fold((0, 0), |(total, soft), card| {
+            analysis::add_card(total, soft, card.value())
+        });
+
+        let stand = if hand.is_blackjack() {
+            // Without a peek the dealer can still tie a natural
+            self.rules.blackjack_ratio() * (1.0 - self.dealer(shoe).blackjack)
+        } else {
+            self.stand(total, shoe)
+        };
+        let hit = if total > 21 {
+            -1.0
+        } else {
+            self.hit(total, soft_aces, shoe)
+        };
+
+        ActionValues {
+            stand,
+            hit,
+            double: available
+                .double
+                .then(|| self.double(total, soft_aces, shoe)),
+            split: (available.split && hand.is_pair())
+                .then(|| self.split(hand.cards()[0].value(), shoe)),
+            // Surrender only saves half the bet once the dealer is known not to have blackjack
+            surrender: available
+                .surrender
+                .then(|| -0.5 - 0.5 * self.dealer(shoe).blackjack),
+        }
+    }
+}
+
+pub fn action_values(
+    hand: &Hand,
+    upcard: &Card,
+    shoe: &ShoeComposition,
+    rules: &Rules,
+    available: Available,
+) -> ActionValues {
+    EvCalculator::new(*rules, upcard.value()).action_values(hand, shoe, available)
+}
+
+pub fn available_for(hand: &Hand, rules: &Rules) -> Available {
+    let first_decision = hand.cards().len() == 2;
+    Available {
+        double: first_decision && (!hand.is_from_split() || rules.double_after_split),
+        split: first_decision && hand.is_pair() && rules.max_split_hands > 1,
+        surrender: first_decision && !hand.is_from_split() && rules.surrender,
+    }
+}
+
+// Returns the hand and the dealer upcard, e.g. from "T 6 vs 9"
+pub fn parse_situation(args: &[String]) -> Result<(Hand, Card), String> {
+    let position = args
+        .iter()
+        .position(|arg| arg == "vs")
+        .ok_or("Expected player cards, then 'vs', then the dealer upcard")?;
+
+    let card = |symbol: &String| {
+        Rank::from_symbol(symbol)
+            .map(|rank| Card {
+                suit: crate::card::Suit::Spades,
+                rank,
+            })
+            .ok_or_else(|| format!("Unknown card: {symbol}"))
+    };
+
+    let mut hand = Hand::new();
+    for symbol in &args[..position] {
+        hand.add_card(card(symbol)?);
+    }
+    if hand.cards().len() < 2 {
+        return Err("The player needs at least two cards".to_string());
+    }
+
+    match &args[position + 1..] {
+        [upcard] => Ok((hand, card(upcard)?)),
+        _ => Err("Expected exactly one dealer upcard after 'vs'".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::Suit;
+
+    fn card(rank: Rank) -> Card {
+        Card {
+            suit: Suit::Clubs,
+            rank,
+        }
+    }
+
+    fn situation(ranks: &[Rank], upcard: Rank, decks: usize) -> (Hand, Card, ShoeComposition) {
+        let mut hand = Hand::new();
+        for &rank in ranks {
+            hand.add_card(card(rank));
+        }
+        let upcard = card(upcard);
+        let shoe = ShoeComposition::full(decks)
+            .without(hand.cards())
+            .without(&[upcard]);
+        (hand, upcard, shoe)
+    }
+
+    #[test]
+    fn test_stand_on_twenty_vs_six() {
+        let rules = Rules::default();
+        let (hand, upcard, shoe) = situation(&[Rank::King, Rank::Queen], Rank::Six, 6);
+        let values = action_values(&hand, &upcard, &shoe, &rules, Available::HIT_STAND);
+
+        assert_eq!(values.best().0, Action::Stand);
+        assert!(values.stand > 0.6);
+        assert!(values.hit < values.stand);
+    }
+
+    #[test]
+    fn test_eleven_vs_six_doubles() {
+        let rules = Rules::default();
+        let (hand, upcard, shoe) = situation(&[Rank::Six, Rank::Five], Rank::Six, 6);
+        let values = action_values(&hand, &upcard, &shoe, &rules, Available::ALL);
+
+        assert_eq!(values.best().0, Action::Double);
+        // Doubling 11 vs 6 is worth roughly +0.67 per unit bet
+        assert!((0.6..0.72).contains(&values.double.unwrap()));
+        assert!(values.split.is_none());
+    }
+
+    #[test]
+    fn test_sixteen_vs_ten_surrenders() {
+        let rules = Rules::default();
+        let (hand, upcard, shoe) = situation(&[Rank::Ten, Rank::Six], Rank::Ten, 6);
+        let values = action_values(&hand, &upcard, &shoe, &rules, Available::ALL);
+
+        assert_eq!(values.best().0, Action::Surrender);
+        // Hitting and standing are both worse than losing half the bet
+        assert!(values.hit < -0.5 && values.stand < -0.5);
+        assert!(values.cost(Action::Stand).unwrap() > 0.0);
+        assert_eq!(values.cost(Action::Surrender), Some(0.0));
+    }
+
+    #[test]
+    fn test_split_eights_vs_seven() {
+        let rules = Rules::default();
+        let (hand, upcard, shoe) = situation(&[Rank::Eight, Rank::Eight], Rank::Seven, 6);
+        let values = action_values(&hand, &upcard, &shoe, &rules, Available::ALL);
+
+        assert_eq!(values.best().0, Action::Split);
+    }
+
+    #[test]
+    fn test_available_for() {
+        let rules = Rules::default();
+        let (hand, _, _) = situation(&[Rank::Eight, Rank::Eight], Rank::Seven, 6);
+        assert_eq!(available_for(&hand, &rules), Available::ALL);
+
+        let game_rules = Rules::single_deck();
+        let available = available_for(&hand, &game_rules);
+        assert!(!available.split);
+        assert!(!available.surrender);
+    }
+
+    #[test]
+    fn test_parse_situation() {
+        let args: Vec<String> = ["A", "7", "vs", "9"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let (hand, upcard) = parse_situation(&args).expect("valid situation");
+        assert_eq!(hand.value(), 18);
+        assert_eq!(upcard.rank, Rank::Nine);
+
+        let missing: Vec<String> = ["A", "7", "9"].iter().map(|s| s.to_string()).collect();
+        assert!(parse_situation(&missing).is_err());
+        let bad: Vec<String> = ["A", "X", "vs", "9"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert!(parse_situation(&bad).is_err());
+    }
+}
@@ -0,0 +1,257 @@
+use std::fs;
+use std::path::PathBuf;
+
+use crate::card::Card;
+use crate::hand::Hand;
+use crate::history::{self, HistorySource, RoundRecord};
+use crate::outcome::Outcome;
+use crate::stats::PlayerStats;
+
+const ROUND_HEADER: &str = "round,session,timestamp,bet,player_cards,dealer_cards,actions,\
+dealer_draws,player_total,dealer_total,outcome,delta,bankroll";
+const SESSION_HEADER: &str = "session,started,ended,rounds,wins,losses,pushes,blackjacks,busts,\
+win_rate,wagered,net,biggest_win,biggest_loss,start_bankroll,end_bankroll";
+
+// Quotes a field only when a spreadsheet would otherwise split or misread it
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn csv_row(fields: &[String]) -> String {
+    let fields: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+    fields.join(",")
+}
+
+fn codes(cards: &[Card]) -> String {
+    let codes: Vec<String> = cards.iter().map(Card::code).collect();
+    codes.join(" ")
+}
+
+fn outcome_name(outcome: Outcome) -> String {
+    serde_json::to_value(outcome)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+fn totals(record: &RoundRecord) -> (u8, u8) {
+    let mut player = Hand::new();
+    for card in record.player_cards.iter().chain(
+        record
+            .actions
+            .iter()
+            .filter_map(|action| action.card.as_ref()),
+    ) {
+        player.add_card(*card);
+    }
+    let mut dealer = Hand::new();
+    for card in record.dealer_cards.iter().chain(&record.dealer_draws) {
+        dealer.add_card(*card);
+    }
+    (player.value(), dealer.value())
+}
+
+// One row per round. Cards are space-separated codes such as "AH TS", and each
+// action is written with the card it drew, e.g. "hit:5S stand".
+pub fn rounds_csv(records: &[RoundRecord]) -> String {
+    let mut lines = vec![ROUND_HEADER.to_string()];
+    for record in records {
+        let actions: Vec<String> = record
+            .actions
+            .iter()
+            .map(|action| {
+                let name = format!("{:?}", action.action).to_lowercase();
+                match action.card {
+                    Some(card) => format!("{name}:{}", card.code()),
+                    None => name,
+                }
+            })
+            .collect();
+        let (player_total, dealer_total) = totals(record);
+
+        lines.push(csv_row(&[
+            record.round.to_string(),
+            record.session.to_string(),
+            record.timestamp.to_string(),
+            record.bet.to_string(),
+            codes(&record.player_cards),
+            codes(&record.dealer_cards),
+            actions.join(" "),
+            codes(&record.dealer_draws),
+            player_total.to_string(),
+            dealer_total.to_string(),
+            outcome_name(record.outcome),
+            record.chip_delta.to_string(),
+            record.chips.to_string(),
+        ]));
+    }
+    lines.join("\n") + "\n"
+}
+
+// One row per session, in the order the sessions were played
+pub fn sessions_csv(records: &[RoundRecord]) -> String {
+    let mut lines = vec![SESSION_HEADER.to_string()];
+    for session in records.chunk_by(|a, b| a.session == b.session) {
+        let mut stats = PlayerStats::new();
+        for record in session {
+            let actions: Vec<_> = record.actions.iter().map(|action| action.action).collect();
+            let upcard = record.dealer_cards.get(1).map(Card::value);
+            stats.record(
+                record.outcome,
+                record.bet,
+                record.chip_delta,
+                upcard,
+                &actions,
+            );
+        }
+
+        let (first, last) = (&session[0], &session[session.len() - 1]);
+        lines.push(csv_row(&[
+            first.session.to_string(),
+            first.timestamp.to_string(),
+            last.timestamp.to_string(),
+            stats.rounds.to_string(),
+            stats.wins.to_string(),
+            stats.losses.to_string(),
+            stats.pushes.to_string(),
+            stats.blackjacks.to_string(),
+            stats.busts.to_string(),
+            format!("{:.1}", stats.win_rate()),
+            stats.wagered.to_string(),
+            stats.net.to_string(),
+            stats.biggest_win.to_string(),
+            stats.biggest_loss.to_string(),
+            (first.chips as i64 - first.chip_delta).to_string(),
+            last.chips.to_string(),
+        ]));
+    }
+    lines.join("\n") + "\n"
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ExportOptions {
+    pub history: HistorySource,
+    pub dir: Option<PathBuf>,
+}
+
+impl ExportOptions {
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let mut options = ExportOptions::default();
+        let mut iter = args.iter();
+
+        while let Some(arg) = iter.next() {
+            if options.history.apply_option(arg, &mut iter)? {
+                continue;
+            }
+            match arg.as_str() {
+                "--dir" => {
+                    let dir = iter.next().ok_or("Missing value for --dir")?;
+                    options.dir = Some(PathBuf::from(dir));
+                }
+                _ => return Err(format!("Unknown option: {arg}")),
+            }
+        }
+
+        Ok(options)
+    }
+
+    // e.g. alice-rounds.csv and alice-sessions.csv, named after the history file
+    pub fn output_paths(&self) -> (PathBuf, PathBuf) {
+        let history = self.history.path();
+        let name = history
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or("history")
+            .to_string();
+        let dir = self.dir.clone().unwrap_or_else(|| PathBuf::from("."));
+        (
+            dir.join(format!("{name}-rounds.csv")),
+            dir.join(format!("{name}-sessions.csv")),
+        )
+    }
+}
+
+pub fn run(options: &ExportOptions) -> Result<(), String> {
+    let records = history::read(&options.history.path())?;
+    let (rounds_path, sessions_path) = options.output_paths();
+    if let Some(dir) = &options.dir {
+        fs::create_dir_all(dir)
+            .map_err(|err| format!("Couldn't create {}: {err}", dir.display()))?;
+    }
+
+    for (path, contents) in [
+        (&rounds_path, rounds_csv(&records)),
+        (&sessions_path, sessions_csv(&records)),
+    ] {
+        fs::write(path, contents)
+            .map_err(|err| format!("Couldn't write {}: {err}", path.display()))?;
+    }
+    println!(
+        "Exported {} rounds to {} and {}",
+        records.len(),
+        rounds_path.display(),
+        sessions_path.display()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::fixtures;
+
+    fn record(round: u64, session: u64, outcome: Outcome, delta: i64, chips: u32) -> RoundRecord {
+        RoundRecord {
+            round,
+            session,
+            timestamp: session + round,
+            outcome,
+            chip_delta: delta,
+            chips,
+            ..fixtures::record()
+        }
+    }
+
+    fn records() -> Vec<RoundRecord> {
+        vec![
+            record(1, 100, Outcome::DealerBust, 10, 1010),
+            record(2, 100, Outcome::DealerWins, -10, 1000),
+            record(3, 200, Outcome::PlayerWins, 10, 760),
+        ]
+    }
+
+    #[test]
+    fn test_rounds_csv() {
+        let csv = rounds_csv(&records());
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], ROUND_HEADER);
+        assert_eq!(
+            lines[1],
+            "1,100,101,10,TH 4C,9S 6D,hit:5S stand,KH,19,25,dealer_bust,10,1010"
+        );
+    }
+
+    #[test]
+    fn test_sessions_csv() {
+        let csv = sessions_csv(&records());
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[1],
+            "100,101,102,2,1,1,0,0,0,50.0,20,0,10,-10,1000,1000"
+        );
+        assert!(lines[2].ends_with(",750,760"));
+    }
+
+    #[test]
+    fn test_quoting() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
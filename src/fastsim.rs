@@ -0,0 +1,592 @@
+use std::thread;
+
+use rand::rngs::SmallRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+use crate::bankroll::BetRamp;
+use crate::betting::{self, BettingSystem, SessionStats};
+use crate::deck;
+use crate::outcome::Outcome;
+use crate::rules::Rules;
+use crate::sim::{PlayerStrategy, SimStats};
+use crate::strategy::{Action, Available, HandKey, Strategy};
+
+const ACE: u8 = 11;
+const MAX_HANDS: usize = 4;
+
+// Hi-Lo tags indexed by card value, with the Ace as 11
+const HI_LO: [i32; 12] = [0, 0, 1, 1, 1, 1, 1, 0, 0, 0, -1, -1];
+
+// Cards are stored by blackjack value only, since suits never matter to the outcome
+pub struct Shoe {
+    cards: Vec<u8>,
+    next: usize,
+}
+
+impl Shoe {
+    pub fn new(decks: usize) -> Self {
+        let mut cards = Vec::with_capacity(decks * 52);
+        for _ in 0..decks * 4 {
+            cards.extend(2..=9);
+            cards.extend([10, 10, 10, 10, ACE]);
+        }
+        Shoe { cards, next: 0 }
+    }
+
+    pub fn shuffle(&mut self, rng: &mut SmallRng) {
+        self.cards.shuffle(rng);
+        self.next = 0;
+    }
+
+    pub fn deal(&mut self) -> Option<u8> {
+        let card = *self.cards.get(self.next)?;
+        self.next += 1;
+        Some(card)
+    }
+
+    pub fn cards_remaining(&self) -> usize {
+        self.cards.len() - self.next
+    }
+}
+
+// Keeps a running total so nothing is recomputed as cards are added
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FastHand {
+    total: u8,
+    soft_aces: u8,
+    cards: u8,
+    first: u8,
+    pair: bool,
+    from_split: bool,
+}
+
+impl FastHand {
+    pub fn new() -> Self {
+        FastHand::default()
+    }
+
+    pub fn from_split(card: u8) -> Self {
+        let mut hand = FastHand {
+            from_split: true,
+            ..FastHand::default()
+        };
+        hand.add(card);
+        hand
+    }
+
+    pub fn add(&mut self, card: u8) {
+        self.pair = self.cards == 1 && self.first == card;
+        if self.cards == 0 {
+            self.first = card;
+        }
+        self.cards += 1;
+        self.total += card;
+        if card == ACE {
+            self.soft_aces += 1;
+        }
+        while self.total > 21 && self.soft_aces > 0 {
+            self.total -= 10;
+            self.soft_aces -= 1;
+        }
+    }
+
+    pub fn value(&self) -> u8 {
+        self.total
+    }
+
+    pub fn is_soft(&self) -> bool {
+        self.soft_aces > 0
+    }
+
+    pub fn is_busted(&self) -> bool {
+        self.total > 21
+    }
+
+    pub fn is_blackjack(&self) -> bool {
+        !self.from_split && self.cards == 2 && self.total == 21
+    }
+
+    pub fn key(&self) -> HandKey {
+        if self.pair {
+            HandKey::Pair(self.first)
+        } else if self.is_soft() {
+            HandKey::Soft(self.total)
+        } else {
+            HandKey::Hard(self.total)
+        }
+    }
+}
+
+enum PlayedHand {
+    Finished(FastHand, f64),
+    Split(u8),
+    Surrendered,
+}
+
+pub struct FastTable {
+    rules: Rules,
+    player: PlayerStrategy,
+    strategy: Strategy,
+    shoe: Shoe,
+    rng: SmallRng,
+    running_count: i32,
+}
+
+impl FastTable {
+    pub fn new(rules: Rules, player: PlayerStrategy, seed: u64) -> Self {
+        let mut table = FastTable {
+            rules,
+            player,
+            strategy: Strategy::new(),
+            shoe: Shoe::new(rules.decks),
+            rng: SmallRng::seed_from_u64(seed),
+            running_count: 0,
+        };
+        table.shuffle();
+        table
+    }
+
+    fn shuffle(&mut self) {
+        self.shoe.shuffle(&mut self.rng);
+        self.running_count = 0;
+    }
+
+    pub fn true_count(&self) -> f64 {
+        crate::count::true_count(self.running_count, self.shoe.cards_remaining())
+    }
+
+    fn draw_hidden(&mut self) -> u8 {
+        if let Some(card) = self.shoe.deal() {
+            return card;
+        }
+        self.shuffle();
+        self.shoe.deal().expect("a fresh shoe has cards")
+    }
+
+    fn draw(&mut self) -> u8 {
+        let card = self.draw_hidden();
+        self.running_count += HI_LO[card as usize];
+        card
+    }
+
+    fn decide(&self, hand: &FastHand, upcard: u8, available: Available) -> Action {
+        let true_count = match self.player {
+            PlayerStrategy::Basic => None,
+            PlayerStrategy::Counting => Some(self.true_count()),
+            PlayerStrategy::MimicDealer => {
+                let hits = self.rules.dealer_hits_total(hand.value(), hand.is_soft());
+                return if hits { Action::Hit } else { Action::Stand };
+            }
+        };
+        self.strategy
+            .recommend_for(hand.key(), upcard, true_count, available)
+            .action
+    }
+
+    fn play_hand(&mut self, mut hand: FastHand, upcard: u8, hands: usize) -> PlayedHand {
+        if hand.cards == 1 {
+            let card = self.draw();
+            hand.add(card);
+        }
+
+        let can_split = hand.pair && hands < self.rules.max_split_hands.min(MAX_HANDS);
+
+        if hand.from_split && hand.first == ACE {
+            if can_split && self.rules.resplit_aces {
+                return PlayedHand::Split(ACE);
+            }
+            return PlayedHand::Finished(hand, 1.0);
+        }
+
+        loop {
+            if hand.value() >= 21 {
+                return PlayedHand::Finished(hand, 1.0);
+            }
+
+            let first_decision = hand.cards == 2;
+            let available = Available {
+                double: first_decision && (!hand.from_split || self.rules.double_after_split),
+                split: first_decision && can_split,
+                surrender: first_decision && !hand.from_split && self.rules.surrender,
+            };
+
+            match self.decide(&hand, upcard, available) {
+                Action::Hit => {
+                    let card = self.draw();
+                    hand.add(card);
+                }
+                Action::Stand => return PlayedHand::Finished(hand, 1.0),
+                Action::Double => {
+                    let card = self.draw();
+                    hand.add(card);
+                    return PlayedHand::Finished(hand, 2.0);
+                }
+                Action::Split => return PlayedHand::Split(hand.first),
+                Action::Surrender => return PlayedHand::Surrendered,
+            }
+        }
+    }
+
+    fn settle(&self, player: &FastHand, dealer: &FastHand) -> f64 {
+        Outcome::from_totals(
+            player.value(),
+            player.is_blackjack(),
+            dealer.value(),
+            dealer.is_blackjack(),
+        )
+        .units(&self.rules)
+    }
+
+    // Reached the cut card, so the next round starts from a fresh shoe and count
+    pub fn shuffle_if_needed(&mut self) {
+        if self.shoe.cards_remaining() <= self.rules.cut_card() {
+            self.shuffle();
+        }
+    }
+
+    // Same round structure as sim::Table::play_round, on the compact representation
+    pub fn play_round(&mut self) -> f64 {
+        self.shuffle_if_needed();
+
+        let mut player = FastHand::new();
+        let mut dealer = FastHand::new();
+        let card = self.draw();
+        player.add(card);
+        let hole_card = self.draw_hidden();
+        dealer.add(hole_card);
+        let card = self.draw();
+        player.add(card);
+        let upcard = self.draw();
+        dealer.add(upcard);
+
+        let mut result = 0.0;
+        if upcard == ACE
+            && self.player == PlayerStrategy::Counting
+            && self.strategy.take_insurance(Some(self.true_count()))
+        {
+            result += if dealer.is_blackjack() { 1.0 } else { -0.5 };
+        }
+
+        let dealer_checks = self.rules.dealer_peeks && dealer.is_blackjack();
+        if dealer_checks || player.is_blackjack() {
+            self.running_count += HI_LO[hole_card as usize];
+            return result + self.settle(&player, &dealer);
+        }
+
+        // Splitting is capped, so fixed arrays avoid allocating every round
+        let mut pending = [FastHand::new(); MAX_HANDS];
+        let mut pending_len = 1;
+        pending[0] = player;
+        let mut finished = [(FastHand::new(), 0.0); MAX_HANDS];
+        let mut finished_len = 0;
+        let mut hands = 1;
+
+        while pending_len > 0 {
+            pending_len -= 1;
+            match self.play_hand(pending[pending_len], upcard, hands) {
+                PlayedHand::Finished(hand, bet) => {
+                    finished[finished_len] = (hand, bet);
+                    finished_len += 1;
+                }
+                PlayedHand::Surrendered if dealer.is_blackjack() => result -= 1.0,
+                PlayedHand::Surrendered => result -= 0.5,
+                PlayedHand::Split(card) => {
+                    hands += 1;
+                    pending[pending_len] = FastHand::from_split(card);
+                    pending[pending_len + 1] = FastHand::from_split(card);
+                    pending_len += 2;
+                }
+            }
+        }
+
+        let finished = &finished[..finished_len];
+        self.running_count += HI_LO[hole_card as usize];
+        if finished.iter().any(|(hand, _)| !hand.is_busted()) {
+            while self
+                .rules
+                .dealer_hits_total(dealer.value(), dealer.is_soft())
+            {
+                let card = self.draw();
+                dealer.add(card);
+            }
+        }
+
+        for (hand, bet) in finished {
+            result += self.settle(hand, &dealer) * bet;
+        }
+        result
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FastConfig {
+    pub rules: Rules,
+    pub player: PlayerStrategy,
+    pub hands: u64,
+    pub seed: u64,
+    pub threads: usize,
+    pub ramp: Option<BetRamp>,
+}
+
+pub fn available_threads() -> usize {
+    thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+}
+
+// SplitMix64, so neighbouring thread indexes get unrelated seeds
+pub fn thread_seed(seed: u64, thread: usize) -> u64 {
+    let mut state = seed.wrapping_add((thread as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    deck::splitmix64(&mut state)
+}
+
+// Each thread plays its own table, and results are merged in thread order so a
+// given seed and thread count always produce the same answer
+pub fn simulate(config: &FastConfig) -> SimStats {
+    let threads = config.threads.max(1) as u64;
+    let per_thread = config.hands / threads;
+    let extra = config.hands % threads;
+
+    let results: Vec<SimStats> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|index| {
+                let hands = per_thread + u64::from(index < extra);
+                let seed = thread_seed(config.seed, index as usize);
+                scope.spawn(move || {
+                    let mut table = FastTable::new(config.rules, config.player, seed);
+                    let mut stats = SimStats::new();
+                    for _ in 0..hands {
+                        let bet = match &config.ramp {
+                            Some(ramp) => {
+                                table.shuffle_if_needed();
+                                ramp.bet(table.true_count()) as f64
+                            }
+                            None => 1.0,
+                        };
+                        stats.record_bet(table.play_round(), bet);
+                    }
+                    stats
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .map(|worker| worker.join().expect("simulation thread panicked"))
+            .collect()
+    });
+
+    let mut stats = SimStats::new();
+    for result in &results {
+        stats.merge(result);
+    }
+    stats
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SessionConfig {
+    pub system: BettingSystem,
+    pub sessions: u64,
+    pub hands: u64,
+    // Bankroll and table limit in units of the base bet
+    pub bankroll: f64,
+    pub table_max: u32,
+}
+
+// Plays sessions of up to `hands` rounds with a betting system, each ending early
+// if the bankroll can no longer cover the minimum bet
+pub fn simulate_sessions(config: &FastConfig, sessions: &SessionConfig) -> SessionStats {
+    let threads = config.threads.max(1) as u64;
+    let per_thread = sessions.sessions / threads;
+    let extra = sessions.sessions % threads;
+
+    let results: Vec<SessionStats> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|index| {
+                let count = per_thread + u64::from(index < extra);
+                let seed = thread_seed(config.seed, index as usize);
+                scope.spawn(move || {
+                    let mut table = FastTable::new(config.rules, config.player, seed);
+                    let mut stats = SessionStats::new();
+                    for _ in 0..count {
+                        let mut strategy = sessions.system.create(1);
+                        let mut bankroll = sessions.bankroll;
+                        let mut largest_bet = 0;
+                        let mut played = 0;
+
+                        while played < sessions.hands && bankroll >= 1.0 {
+                            table.shuffle_if_needed();
+                            let bet = strategy.next_bet(bankroll, table.true_count());
+                            let bet = betting::cap_bet(bet, bankroll, sessions.table_max).max(1);
+                            let net = table.play_round() * bet as f64;
+                            strategy.record(bet, net);
+                            bankroll += net;
+                            largest_bet = largest_bet.max(bet);
+                            played += 1;
+                        }
+
+                        let broke = bankroll < 1.0;
+                        stats.record(sessions.bankroll, bankroll, played, largest_bet, broke);
+                    }
+                    stats
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .map(|worker| worker.join().expect("simulation thread panicked"))
+            .collect()
+    });
+
+    let mut stats = SessionStats::new();
+    for result in &results {
+        stats.merge(result);
+    }
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shoe_composition() {
+        let shoe = Shoe::new(2);
+        assert_eq!(shoe.cards_remaining(), 104);
+        assert_eq!(shoe.cards.iter().filter(|&&c| c == 10).count(), 32);
+        assert_eq!(shoe.cards.iter().filter(|&&c| c == ACE).count(), 8);
+
+        let count: i32 = shoe.cards.iter().map(|&c| HI_LO[c as usize]).sum();
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn test_fast_hand_matches_hand() {
+        let mut hand = FastHand::new();
+        hand.add(ACE);
+        hand.add(6);
+        assert_eq!(hand.value(), 17);
+        assert!(hand.is_soft());
+        assert_eq!(hand.key(), HandKey::Soft(17));
+
+        hand.add(9);
+        assert_eq!(hand.value(), 16);
+        assert!(!hand.is_soft());
+
+        let mut aces = FastHand::new();
+        aces.add(ACE);
+        aces.add(ACE);
+        assert_eq!(aces.value(), 12);
+        assert_eq!(aces.key(), HandKey::Pair(ACE));
+
+        let mut split = FastHand::from_split(ACE);
+        split.add(10);
+        assert_eq!(split.value(), 21);
+        assert!(!split.is_blackjack());
+    }
+
+    #[test]
+    fn test_deterministic_for_seed_and_threads() {
+        let config = FastConfig {
+            rules: Rules::default(),
+            player: PlayerStrategy::Counting,
+            hands: 20_000,
+            seed: 42,
+            threads: 3,
+            ramp: None,
+        };
+
+        let first = simulate(&config);
+        let second = simulate(&config);
+        assert_eq!(first, second);
+        assert_eq!(first.hands, 20_000);
+
+        let other_seed = simulate(&FastConfig { seed: 43, ..config });
+        assert_ne!(first.total, other_seed.total);
+    }
+
+    #[test]
+    fn test_bet_ramp_beats_flat_betting() {
+        let config = FastConfig {
+            rules: Rules::default(),
+            player: PlayerStrategy::Counting,
+            hands: 400_000,
+            seed: 5,
+            threads: 2,
+            ramp: None,
+        };
+        let flat = simulate(&config);
+        let spread = simulate(&FastConfig {
+            ramp: BetRamp::parse("1,2,4,6,8,12").ok(),
+            ..config
+        });
+
+        assert_eq!(flat.average_bet(), 1.0);
+        assert!(spread.average_bet() > 1.0);
+        // Betting more when the count is high turns the count into an edge per unit
+        assert!(spread.expected_value() > flat.expected_value());
+    }
+
+    #[test]
+    fn test_martingale_goes_broke_more_often() {
+        let config = FastConfig {
+            rules: Rules::default(),
+            player: PlayerStrategy::Basic,
+            hands: 0,
+            seed: 11,
+            threads: 2,
+            ramp: None,
+        };
+        let sessions = SessionConfig {
+            system: BettingSystem::Flat,
+            sessions: 400,
+            hands: 500,
+            bankroll: 100.0,
+            table_max: 100,
+        };
+
+        let flat = simulate_sessions(&config, &sessions);
+        let martingale = simulate_sessions(
+            &config,
+            &SessionConfig {
+                system: BettingSystem::Martingale,
+                ..sessions
+            },
+        );
+
+        assert_eq!(flat.sessions, 400);
+        assert!(martingale.broke > flat.broke);
+        assert!(martingale.largest_bet > flat.largest_bet);
+        assert_eq!(flat.largest_bet, 1);
+    }
+
+    #[test]
+    fn test_thread_seeds_differ() {
+        assert_ne!(thread_seed(7, 0), thread_seed(7, 1));
+        assert_eq!(thread_seed(7, 2), thread_seed(7, 2));
+    }
+
+    #[test]
+    fn test_agrees_with_reference_simulator() {
+        let rules = Rules::default();
+        let fast = simulate(&FastConfig {
+            rules,
+            player: PlayerStrategy::Basic,
+            hands: 200_000,
+            seed: 1,
+            threads: 4,
+            ramp: None,
+        });
+        let reference = crate::sim::simulate_seeded(rules, PlayerStrategy::Basic, 100_000, 1);
+
+        // Both should land near the -0.4% edge of these rules; both runs are seeded, so the
+        // sampling noise allowed for here is the same on every run
+        let difference = (fast.expected_value() - reference.expected_value()).abs();
+        assert!(
+            difference < 0.02,
+            "fast and reference differ by {difference}"
+        );
+    }
+}
@@ -1,17 +1,54 @@
 use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process;
 
-use crate::deck::Deck;
+use rand::{rng, Rng};
+
+use crate::analysis::{self, ShoeComposition};
+use crate::betting::{self, BettingStrategy, BettingSystem};
+use crate::card::{Card, Rank};
+use crate::count::RunningCount;
+use crate::deck::{self, Deck};
+use crate::drill::{self, DrillStats};
 use crate::hand::Hand;
+use crate::history::{self, HandHistory, PlayerAction, RoundRecord};
+use crate::keys::{self, Key};
+use crate::outcome::Outcome;
+use crate::plot::{self, BalanceSeries};
+use crate::profile::{Profile, ProfileStore, STARTING_CHIPS};
+use crate::review::{Decision, Review};
+use crate::rules::Rules;
+use crate::save::{self, SavedGame, SAVE_VERSION};
+use crate::stats::PlayerStats;
+use crate::strategy::{Action, Available, Strategy};
 
 #[cfg(test)]
-use crate::card::{Card, Rank, Suit};
+use crate::card::Suit;
 
 pub struct Game {
     pub(crate) deck: Deck,
+    pub(crate) shoe_seed: Option<u64>,
+    pub(crate) shoe_position: usize,
     pub(crate) player_hand: Hand,
     pub(crate) dealer_hand: Hand,
     pub(crate) player_chips: u32,
     pub(crate) current_bet: u32,
+    pub(crate) running_count: RunningCount,
+    pub(crate) count_check_chance: f64,
+    pub(crate) count_checks: DrillStats,
+    pub(crate) strategy: Strategy,
+    pub(crate) rules: Rules,
+    pub(crate) show_odds: bool,
+    pub(crate) show_review: bool,
+    pub(crate) review: Review,
+    pub(crate) betting: Option<(BettingSystem, Box<dyn BettingStrategy>)>,
+    pub(crate) profile: Option<(Profile, ProfileStore)>,
+    pub(crate) save_path: Option<PathBuf>,
+    pub(crate) actions: Vec<PlayerAction>,
+    pub(crate) history: Option<HandHistory>,
+    pub(crate) session_stats: PlayerStats,
+    pub(crate) balances: Vec<i64>,
+    pub(crate) single_key: bool,
 }
 
 impl Default for Game {
@@ -22,23 +59,253 @@ impl Default for Game {
 
 impl Game {
     pub fn new() -> Self {
+        let seed = rng().random();
         let mut deck = Deck::new();
-        deck.shuffle();
+        deck.shuffle_seeded(seed);
         Game {
             deck,
+            shoe_seed: Some(seed),
+            shoe_position: 0,
             player_hand: Hand::new(),
             dealer_hand: Hand::new(),
-            player_chips: 1000,
+            player_chips: STARTING_CHIPS,
             current_bet: 0,
+            running_count: RunningCount::new(),
+            count_check_chance: 0.0,
+            count_checks: DrillStats::new(),
+            strategy: Strategy::new(),
+            rules: Rules::single_deck(),
+            show_odds: false,
+            show_review: false,
+            review: Review::new(),
+            betting: None,
+            profile: None,
+            save_path: None,
+            actions: Vec::new(),
+            history: None,
+            session_stats: PlayerStats::new(),
+            balances: Vec::new(),
+            single_key: false,
+        }
+    }
+
+    pub fn with_odds(mut self, show_odds: bool) -> Self {
+        self.show_odds = show_odds;
+        self
+    }
+
+    pub fn with_review(mut self, show_review: bool) -> Self {
+        self.show_review = show_review;
+        self
+    }
+
+    pub fn with_betting(mut self, system: BettingSystem, base_bet: u32) -> Self {
+        self.betting = Some((system, system.create(base_bet)));
+        self
+    }
+
+    pub fn with_profile(mut self, profile: Profile, store: ProfileStore) -> Self {
+        self.player_chips = profile.bankroll;
+        self.profile = Some((profile, store));
+        self
+    }
+
+    // Saves the shoe and bankroll to this file after every round
+    pub fn with_save_file(mut self, path: PathBuf) -> Self {
+        self.save_path = Some(path);
+        self
+    }
+
+    pub fn with_history(mut self, history: HandHistory) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    pub fn with_saved_game(mut self, saved: SavedGame) -> Self {
+        self.deck = saved.deck;
+        self.shoe_seed = saved.shoe_seed;
+        self.player_hand = saved.player_hand;
+        self.dealer_hand = saved.dealer_hand;
+        self.player_chips = saved.player_chips;
+        self.current_bet = saved.current_bet;
+        self.running_count = saved.running_count;
+        self.rules = saved.rules;
+        self
+    }
+
+    pub fn snapshot(&self) -> SavedGame {
+        SavedGame {
+            version: SAVE_VERSION,
+            deck: self.deck.clone(),
+            shoe_seed: self.shoe_seed,
+            player_hand: self.player_hand.clone(),
+            dealer_hand: self.dealer_hand.clone(),
+            player_chips: self.player_chips,
+            current_bet: self.current_bet,
+            running_count: self.running_count,
+            rules: self.rules,
+        }
+    }
+
+    pub fn with_strategy(mut self, strategy: Strategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    // Reads single key presses instead of whole lines
+    pub fn with_single_key(mut self, single_key: bool) -> Self {
+        self.single_key = single_key;
+        self
+    }
+
+    pub fn with_count_checks(mut self, chance: f64) -> Self {
+        self.count_check_chance = chance;
+        self
+    }
+
+    pub(crate) fn true_count(&self) -> f64 {
+        self.running_count.true_count(self.deck.cards_remaining())
+    }
+
+    fn maybe_check_count(&mut self) {
+        if self.count_check_chance <= 0.0 || !rng().random_bool(self.count_check_chance) {
+            return;
+        }
+
+        let (correct, elapsed) = drill::count_check(self.running_count.value(), self.true_count());
+        self.count_checks.record(correct, elapsed);
+
+        // The count only matters for the hand when it triggers an index play
+        let Some(upcard) = self.dealer_upcard() else {
+            return;
+        };
+        let recommendation = self.strategy.recommend(
+            &self.player_hand,
+            upcard,
+            Some(self.true_count()),
+            Available::HIT_STAND,
+        );
+        if let Some(play) = recommendation.deviation {
+            println!(
+                "At this count, index play {play} overrides basic strategy ({}).\n",
+                recommendation.basic
+            );
+        }
+    }
+
+    pub(crate) fn suggested_bet(&mut self) -> Option<(BettingSystem, u32)> {
+        let true_count = self.true_count();
+        let chips = self.player_chips;
+        let (system, strategy) = self.betting.as_mut()?;
+        let bet = strategy.next_bet(chips as f64, true_count);
+        Some((*system, betting::cap_bet(bet, chips as f64, u32::MAX)))
+    }
+
+    fn print_stats(&self) {
+        println!("\n{}\n", self.session_stats.render("This session"));
+        if let Some((profile, _)) = &self.profile {
+            let title = format!("Lifetime: {}", profile.name);
+            println!("{}\n", profile.stats.render(&title));
+        }
+    }
+
+    fn print_chart(&self) {
+        let series = BalanceSeries::new(self.balances.clone());
+        println!(
+            "\n{}\n",
+            series.render_terminal(plot::DEFAULT_WIDTH, plot::DEFAULT_HEIGHT)
+        );
+    }
+
+    fn read_key() -> Key {
+        match keys::read_key() {
+            Ok(Key::Interrupt) => {
+                println!();
+                process::exit(130);
+            }
+            Ok(key) => key,
+            Err(err) => {
+                println!("\nCouldn't read a key press: {err}");
+                process::exit(1);
+            }
+        }
+    }
+
+    // Arrow keys move the bet, digits type a new one and Enter deals with it,
+    // so pressing Enter again rebets the last amount
+    fn place_bet_by_keys(&mut self) -> bool {
+        let default = match self.suggested_bet() {
+            Some((_, bet)) => bet,
+            None if self.current_bet > 0 => self.current_bet,
+            None => 10,
+        };
+        let mut bet = default.clamp(1, self.player_chips.max(1));
+        let mut typed = String::new();
+        println!(
+            "You have {} chips. Arrow keys change the bet by {}, Enter deals, t shows stats, c the chart and q quits.",
+            self.player_chips,
+            keys::BET_STEP
+        );
+        loop {
+            print!("\rBet: {bet:<12}");
+            io::stdout().flush().unwrap();
+
+            match Self::read_key() {
+                Key::Enter => {
+                    println!();
+                    self.current_bet = bet;
+                    return true;
+                }
+                Key::Char('q') | Key::Esc => {
+                    println!();
+                    return false;
+                }
+                Key::Char('t') => {
+                    println!();
+                    self.print_stats();
+                }
+                Key::Char('c') => {
+                    println!();
+                    self.print_chart();
+                }
+                Key::Char(digit) if digit.is_ascii_digit() && typed.len() < 9 => {
+                    typed.push(digit);
+                    bet = typed
+                        .parse::<u32>()
+                        .unwrap_or(bet)
+                        .clamp(1, self.player_chips.max(1));
+                }
+                Key::Backspace => {
+                    typed.pop();
+                    bet = typed
+                        .parse::<u32>()
+                        .unwrap_or(default)
+                        .clamp(1, self.player_chips.max(1));
+                }
+                key => {
+                    typed.clear();
+                    bet = keys::adjust_bet(bet, key, self.player_chips);
+                }
+            }
         }
     }
 
     fn place_bet(&mut self) -> bool {
+        if self.single_key {
+            return self.place_bet_by_keys();
+        }
+        let suggestion = self.suggested_bet();
         loop {
-            print!(
-                "You have {} chips. Enter your bet (or 0 to quit): ",
-                self.player_chips
-            );
+            match suggestion {
+                Some((system, bet)) => print!(
+                    "You have {} chips. Enter your bet, press Enter to bet {bet} ({system}), or 0 to quit: ",
+                    self.player_chips
+                ),
+                None => print!(
+                    "You have {} chips. Enter your bet (or 0 to quit): ",
+                    self.player_chips
+                ),
+            }
             io::stdout().flush().unwrap();
 
             let mut input = String::new();
@@ -50,6 +317,20 @@ impl Game {
                 }
             }
 
+            if input.trim().eq_ignore_ascii_case("stats") {
+                self.print_stats();
+                continue;
+            }
+            if input.trim().eq_ignore_ascii_case("chart") {
+                self.print_chart();
+                continue;
+            }
+
+            if let (Some((_, bet)), "") = (suggestion, input.trim()) {
+                self.current_bet = bet;
+                return true;
+            }
+
             match input.trim().parse::<u32>() {
                 Ok(0) => return false,
                 Ok(bet) if bet <= self.player_chips => {
@@ -61,7 +342,9 @@ impl Game {
                     continue;
                 }
                 Err(_) => {
-                    println!("Invalid input! Please enter a number.");
+                    println!(
+                        "Invalid input! Please enter a number, 'stats' for your statistics or 'chart' for your bankroll."
+                    );
                     continue;
                 }
             }
@@ -71,6 +354,8 @@ impl Game {
     pub(crate) fn deal_initial_cards(&mut self) {
         self.player_hand.clear();
         self.dealer_hand.clear();
+        self.actions.clear();
+        self.shoe_position = (self.rules.decks * 52).saturating_sub(self.deck.cards_remaining());
 
         for _ in 0..2 {
             if let Some(card) = self.deck.deal() {
@@ -80,6 +365,124 @@ impl Game {
                 self.dealer_hand.add_card(card);
             }
         }
+
+        // The dealer's first card stays face down until the hand is revealed
+        for card in self.player_hand.cards() {
+            self.running_count.observe(card);
+        }
+        if let Some(upcard) = self.dealer_hand.cards().get(1) {
+            self.running_count.observe(upcard);
+        }
+    }
+
+    pub(crate) fn dealer_upcard(&self) -> Option<&Card> {
+        self.dealer_hand.cards().get(1)
+    }
+
+    pub(crate) fn hint(&self) -> Option<String> {
+        let upcard = self.dealer_upcard()?;
+        let true_count = Some(self.true_count());
+        let recommendation =
+            self.strategy
+                .recommend(&self.player_hand, upcard, true_count, Available::HIT_STAND);
+
+        let mut hint = format!("Hint: {recommendation}");
+        if upcard.rank == Rank::Ace && self.player_hand.cards().len() == 2 {
+            if self.strategy.take_insurance(true_count) {
+                hint.push_str("\nInsurance would be worth taking at this count.");
+            } else {
+                hint.push_str("\nInsurance is not worth taking at this count.");
+            }
+        }
+        Some(hint)
+    }
+
+    // Everything the player hasn't seen: the undealt cards plus the dealer's hole card
+    pub(crate) fn unseen_cards(&self) -> ShoeComposition {
+        let mut unseen = ShoeComposition::from_cards(self.deck.cards());
+        if let Some(hole_card) = self.dealer_hand.cards().first() {
+            unseen.add(hole_card.value());
+        }
+        unseen
+    }
+
+    pub(crate) fn odds(&self) -> Option<String> {
+        let upcard = self.dealer_upcard()?;
+
+        // The hole card is one of the unseen cards, so the dealer draws from those
+        let unseen = self.unseen_cards();
+        let bust_chance = analysis::bust_probability(
+            self.player_hand.value(),
+            self.player_hand.is_soft(),
+            &unseen,
+        );
+        let dealer = analysis::dealer_outcomes(upcard.value(), &unseen, &self.rules);
+
+        Some(crate::display::render_odds(bust_chance, upcard, &dealer))
+    }
+
+    fn print_odds(&self) {
+        if !self.show_odds {
+            return;
+        }
+        if let Some(odds) = self.odds() {
+            println!("{odds}");
+            println!();
+        }
+    }
+
+    pub(crate) fn record_decision(&mut self, chosen: Action) {
+        if !self.show_review {
+            return;
+        }
+        let Some(upcard) = self.dealer_upcard() else {
+            return;
+        };
+
+        let recommendation = self.strategy.recommend(
+            &self.player_hand,
+            upcard,
+            Some(self.true_count()),
+            Available::HIT_STAND,
+        );
+        let decision = Decision::evaluate(
+            &self.player_hand,
+            upcard,
+            &self.unseen_cards(),
+            &self.rules,
+            Available::HIT_STAND,
+            chosen,
+        )
+        .with_strategy(recommendation);
+        self.review.record(decision, self.current_bet);
+    }
+
+    pub(crate) fn save_profile(&mut self) -> Result<(), String> {
+        let Some((profile, store)) = &mut self.profile else {
+            return Ok(());
+        };
+        profile.bankroll = self.player_chips;
+        profile.preferences.show_odds = self.show_odds;
+        profile.preferences.review = self.show_review;
+        profile.preferences.count_checks = self.count_check_chance > 0.0;
+        store.save(profile)
+    }
+
+    // A game that ran out of chips can't be resumed, so its save is removed
+    pub(crate) fn save_session(&self) -> Result<(), String> {
+        let Some(path) = &self.save_path else {
+            return Ok(());
+        };
+        if self.player_chips == 0 {
+            return save::remove(path);
+        }
+        self.snapshot().save(path)
+    }
+
+    pub(crate) fn reveal_hole_card(&mut self) {
+        if let Some(hole_card) = self.dealer_hand.cards().first() {
+            self.running_count.observe(hole_card);
+        }
     }
 
     fn display_hands(&self, hide_dealer_card: bool) {
@@ -105,6 +508,10 @@ impl Game {
         println!("\n=== Your Hand ===");
         println!("{}", self.player_hand);
         println!();
+
+        if hide_dealer_card {
+            self.print_odds();
+        }
     }
 
     fn player_turn(&mut self) -> bool {
@@ -119,62 +526,182 @@ impl Game {
                 return true;
             }
 
-            print!("Hit (h) or Stand (s)? ");
+            self.maybe_check_count();
+
+            print!("Hit (h), Stand (s), Hint (?) or Odds (o)? ");
             io::stdout().flush().unwrap();
 
             let mut input = String::new();
-            io::stdin().read_line(&mut input).unwrap();
+            if self.single_key {
+                if let Key::Char(key) = Self::read_key() {
+                    input.push(key);
+                }
+                println!("{input}");
+            } else {
+                io::stdin().read_line(&mut input).unwrap();
+            }
 
             match input.trim().to_lowercase().as_str() {
                 "h" | "hit" => {
-                    if let Some(card) = self.deck.deal() {
-                        self.player_hand.add_card(card);
-                        println!("You drew: {card}");
+                    if let Some(card) = self.hit() {
+                        println!("You drew: {}", crate::display::card_label(&card));
                         println!("\n=== Your Hand ===");
                         println!("{}", self.player_hand);
                         println!();
+                        if !self.player_hand.is_busted() {
+                            self.print_odds();
+                        }
+                    }
+                }
+                "s" | "stand" => {
+                    self.stand();
+                    return true;
+                }
+                "?" | "hint" => {
+                    if let Some(hint) = self.hint() {
+                        println!("{hint}");
+                    }
+                }
+                "o" | "odds" => {
+                    self.show_odds = !self.show_odds;
+                    if self.show_odds {
+                        println!();
+                        self.print_odds();
+                    } else {
+                        println!("Odds hidden.");
                     }
                 }
-                "s" | "stand" => return true,
-                _ => println!("Invalid input! Please enter 'h' for hit or 's' for stand."),
+                "d" | "double" | "p" | "split" | "r" | "surrender" => {
+                    println!("This table only offers hit and stand.")
+                }
+                _ => println!(
+                    "Invalid input! Please enter 'h' for hit, 's' for stand, '?' for a hint or 'o' for odds."
+                ),
             }
         }
     }
 
-    pub(crate) fn dealer_turn(&mut self) {
-        while self.dealer_hand.value() < 17 {
-            if let Some(card) = self.deck.deal() {
-                self.dealer_hand.add_card(card);
-                println!("Dealer drew: {card}");
-            }
+    pub(crate) fn hit(&mut self) -> Option<Card> {
+        self.record_decision(Action::Hit);
+        let card = self.deck.deal()?;
+        self.actions.push(PlayerAction {
+            action: Action::Hit,
+            card: Some(card),
+        });
+        self.player_hand.add_card(card);
+        self.running_count.observe(&card);
+        Some(card)
+    }
+
+    pub(crate) fn stand(&mut self) {
+        self.record_decision(Action::Stand);
+        self.actions.push(PlayerAction {
+            action: Action::Stand,
+            card: None,
+        });
+    }
+
+    // Returns the cards the dealer drew
+    pub(crate) fn dealer_turn(&mut self) -> Vec<Card> {
+        let mut drawn = Vec::new();
+        while self.rules.dealer_hits(&self.dealer_hand) {
+            let Some(card) = self.deck.deal() else {
+                break;
+            };
+            self.dealer_hand.add_card(card);
+            self.running_count.observe(&card);
+            drawn.push(card);
         }
+        drawn
     }
 
-    pub(crate) fn determine_winner(&mut self) {
-        let player_value = self.player_hand.value();
-        let dealer_value = self.dealer_hand.value();
-
-        if self.player_hand.is_busted() {
-            println!("You lose! You busted.");
-            self.player_chips -= self.current_bet;
-        } else if self.dealer_hand.is_busted() {
-            println!("You win! Dealer busted.");
-            self.player_chips += self.current_bet;
-        } else if self.player_hand.is_blackjack() && !self.dealer_hand.is_blackjack() {
-            println!("You win with Blackjack!");
-            self.player_chips += (self.current_bet * 3) / 2;
-        } else if self.dealer_hand.is_blackjack() && !self.player_hand.is_blackjack() {
-            println!("Dealer wins with Blackjack!");
-            self.player_chips -= self.current_bet;
-        } else if player_value > dealer_value {
-            println!("You win!");
-            self.player_chips += self.current_bet;
-        } else if dealer_value > player_value {
-            println!("Dealer wins!");
-            self.player_chips -= self.current_bet;
-        } else {
-            println!("Push! It's a tie.");
+    pub(crate) fn determine_winner(&mut self) -> (Outcome, i64) {
+        let outcome = Outcome::of(&self.player_hand, &self.dealer_hand);
+
+        let delta = outcome.chip_delta(self.current_bet, &self.rules);
+        if let Some((_, strategy)) = &mut self.betting {
+            strategy.record(self.current_bet, delta as f64);
         }
+        let upcard = self.dealer_upcard().map(Card::value);
+        let actions: Vec<Action> = self.actions.iter().map(|action| action.action).collect();
+        self.session_stats
+            .record(outcome, self.current_bet, delta, upcard, &actions);
+        if let Some((profile, _)) = &mut self.profile {
+            profile
+                .stats
+                .record(outcome, self.current_bet, delta, upcard, &actions);
+        }
+        self.player_chips = u32::try_from(self.player_chips as i64 + delta).unwrap_or(0);
+        (outcome, delta)
+    }
+
+    pub(crate) fn round_record(
+        &self,
+        round: u64,
+        session: u64,
+        outcome: Outcome,
+        delta: i64,
+    ) -> RoundRecord {
+        let cards = |hand: &Hand| hand.cards().iter().take(2).copied().collect();
+        let mut record = RoundRecord {
+            round,
+            session,
+            timestamp: history::timestamp(),
+            seed: self.shoe_seed,
+            shoe_position: self.shoe_position,
+            shoe_hash: None,
+            rules: self.rules,
+            bet: self.current_bet,
+            player_cards: cards(&self.player_hand),
+            dealer_cards: cards(&self.dealer_hand),
+            actions: self.actions.clone(),
+            dealer_draws: self.dealer_hand.cards().iter().skip(2).copied().collect(),
+            outcome,
+            chip_delta: delta,
+            chips: self.player_chips,
+        };
+        // The shoe from the start of the round is this round's cards followed by the rest
+        let shoe: Vec<Card> = record.dealt_cards().chain(self.deck.upcoming()).collect();
+        record.shoe_hash = Some(deck::order_hash(shoe));
+        record
+    }
+
+    fn log_round(&mut self, outcome: Outcome, delta: i64) -> Result<(), String> {
+        let Some(mut history) = self.history.take() else {
+            return Ok(());
+        };
+        let result = history.append(&self.round_record(
+            history.next_round(),
+            history.session(),
+            outcome,
+            delta,
+        ));
+        self.history = Some(history);
+        result
+    }
+
+    pub(crate) fn reshuffle_if_low(&mut self) -> bool {
+        if self.deck.cards_remaining() >= 10 {
+            return false;
+        }
+        let seed = rng().random();
+        self.deck = Deck::new();
+        self.deck.shuffle_seeded(seed);
+        self.shoe_seed = Some(seed);
+        self.running_count.reset();
+        true
+    }
+
+    // Pays out the round, then logs and saves it. Failing to save doesn't undo
+    // the round, so the error is returned alongside the result.
+    pub(crate) fn settle_round(&mut self) -> (Outcome, i64, Result<(), String>) {
+        let (outcome, delta) = self.determine_winner();
+        self.balances.push(self.player_chips as i64);
+        let saved = self
+            .log_round(outcome, delta)
+            .and_then(|_| self.save_profile())
+            .and_then(|_| self.save_session());
+        (outcome, delta, saved)
     }
 
     fn play_round(&mut self) -> bool {
@@ -182,23 +709,32 @@ impl Game {
             return false;
         }
 
-        if self.deck.cards_remaining() < 10 {
+        if self.reshuffle_if_low() {
             println!("Shuffling deck...");
-            self.deck = Deck::new();
-            self.deck.shuffle();
         }
 
+        self.review.start_round();
         self.deal_initial_cards();
         self.display_hands(true);
 
         if self.player_turn() {
+            self.reveal_hole_card();
             self.display_hands(false);
-            self.dealer_turn();
+            for card in self.dealer_turn() {
+                println!("Dealer drew: {}", crate::display::card_label(&card));
+            }
             self.display_hands(false);
         }
 
-        self.determine_winner();
+        let (outcome, _, saved) = self.settle_round();
+        println!("{}", crate::display::render_outcome(outcome));
         println!("You now have {} chips.\n", self.player_chips);
+        if let Err(err) = saved {
+            println!("Warning: {err}");
+        }
+        if self.show_review {
+            self.review.print_round(self.current_bet);
+        }
 
         self.player_chips > 0
     }
@@ -206,6 +742,7 @@ impl Game {
     pub fn run(&mut self) {
         println!("Welcome to Blackjack!");
         println!("==================");
+        self.start_session();
 
         while self.play_round() {
             if self.player_chips == 0 {
@@ -214,8 +751,26 @@ impl Game {
             }
         }
 
+        self.print_summary();
         println!("Thanks for playing!");
     }
+
+    pub(crate) fn start_session(&mut self) {
+        self.balances = vec![self.player_chips as i64];
+    }
+
+    pub(crate) fn print_summary(&self) {
+        if self.count_checks.attempts > 0 {
+            self.count_checks.print_summary("Count checks");
+        }
+        if self.show_review && self.review.decisions > 0 {
+            self.review.print_summary();
+        }
+        if self.session_stats.rounds > 0 {
+            println!("\n{}\n", self.session_stats.render("This session"));
+            self.print_chart();
+        }
+    }
 }
 
 #[cfg(test)]
@@ -384,6 +939,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_determine_winner_blackjack_beats_dealer_bust() {
+        let mut game = Game::new();
+        game.current_bet = 100;
+        let initial_chips = game.player_chips;
+
+        game.player_hand.add_card(Card {
+            suit: Suit::Hearts,
+            rank: Rank::Ace,
+        });
+        game.player_hand.add_card(Card {
+            suit: Suit::Diamonds,
+            rank: Rank::King,
+        });
+
+        // Dealer draws to 16 and busts
+        game.dealer_hand.add_card(Card {
+            suit: Suit::Spades,
+            rank: Rank::Ten,
+        });
+        game.dealer_hand.add_card(Card {
+            suit: Suit::Hearts,
+            rank: Rank::Six,
+        });
+        game.dealer_hand.add_card(Card {
+            suit: Suit::Clubs,
+            rank: Rank::Nine,
+        });
+
+        game.determine_winner();
+        assert_eq!(game.player_chips, initial_chips + 150);
+    }
+
     #[test]
     fn test_determine_winner_push() {
         let mut game = Game::new();
@@ -428,4 +1016,199 @@ mod tests {
         // This would trigger a reshuffle in play_round, but we can't test play_round
         // due to I/O operations. Instead, we just verify the condition is correct.
     }
+
+    #[test]
+    fn test_running_count_skips_hole_card() {
+        let mut game = Game::new();
+        game.deal_initial_cards();
+
+        assert_eq!(game.running_count.cards_seen(), 3);
+
+        game.reveal_hole_card();
+        assert_eq!(game.running_count.cards_seen(), 4);
+    }
+
+    #[test]
+    fn test_hint_reports_index_play() {
+        let mut game = Game::new();
+        game.player_hand.add_card(Card {
+            suit: Suit::Hearts,
+            rank: Rank::Ten,
+        });
+        game.player_hand.add_card(Card {
+            suit: Suit::Clubs,
+            rank: Rank::Six,
+        });
+        game.dealer_hand.add_card(Card {
+            suit: Suit::Spades,
+            rank: Rank::Five,
+        });
+        game.dealer_hand.add_card(Card {
+            suit: Suit::Diamonds,
+            rank: Rank::King,
+        });
+
+        // Fresh deck, so the true count is zero and 16 vs 10 stands
+        let hint = game.hint().expect("dealer has an upcard");
+        assert!(hint.starts_with("Hint: Stand"));
+        assert!(hint.contains("overrides basic strategy: Hit"));
+    }
+
+    #[test]
+    fn test_odds_include_hole_card_as_unseen() {
+        let mut game = Game::new();
+        game.deal_initial_cards();
+
+        // 52 cards, less the two player cards and the dealer's upcard
+        assert_eq!(game.unseen_cards().total(), 49);
+
+        let odds = game.odds().expect("dealer has an upcard");
+        assert!(odds.contains("Bust if you hit"));
+        assert!(odds.contains("Dealer showing"));
+    }
+
+    #[test]
+    fn test_record_decision() {
+        let mut game = Game::new();
+        game.current_bet = 10;
+        game.deal_initial_cards();
+
+        // Nothing is evaluated unless the review is turned on
+        game.record_decision(Action::Stand);
+        assert_eq!(game.review.decisions, 0);
+
+        game.show_review = true;
+        game.record_decision(Action::Stand);
+        assert_eq!(game.review.decisions, 1);
+        assert_eq!(game.review.round[0].chosen, Action::Stand);
+        assert_eq!(game.review.round[0].cards.len(), 2);
+    }
+
+    #[test]
+    fn test_betting_system_follows_results() {
+        let mut game = Game::new().with_betting(BettingSystem::Martingale, 10);
+        assert_eq!(game.suggested_bet().map(|(_, bet)| bet), Some(10));
+
+        // A busted hand loses, so the Martingale doubles up
+        game.current_bet = 10;
+        for rank in [Rank::Ten, Rank::Nine, Rank::Five] {
+            game.player_hand.add_card(Card {
+                suit: Suit::Hearts,
+                rank,
+            });
+        }
+        game.determine_winner();
+        assert_eq!(game.player_chips, 990);
+        assert_eq!(game.suggested_bet().map(|(_, bet)| bet), Some(20));
+
+        // The suggestion never asks for more chips than the player has
+        game.player_chips = 15;
+        assert_eq!(game.suggested_bet().map(|(_, bet)| bet), Some(15));
+        assert!(Game::new().suggested_bet().is_none());
+    }
+
+    #[test]
+    fn test_profile_saved_after_round() {
+        let dir = std::env::temp_dir().join(format!("blackjack-cli-game-{}", std::process::id()));
+        let store = ProfileStore::new(dir.clone());
+        let mut profile = Profile::new("tester");
+        profile.bankroll = 500;
+
+        let mut game = Game::new()
+            .with_profile(profile, ProfileStore::new(dir.clone()))
+            .with_odds(true);
+        assert_eq!(game.player_chips, 500);
+
+        game.current_bet = 50;
+        for rank in [Rank::Ten, Rank::Nine, Rank::Five] {
+            game.player_hand.add_card(Card {
+                suit: Suit::Hearts,
+                rank,
+            });
+        }
+        game.determine_winner();
+        game.save_profile().expect("saved");
+        assert_eq!(game.session_stats.busts, 1);
+
+        let saved = store.load("tester").unwrap().expect("profile exists");
+        assert_eq!(saved.bankroll, 450);
+        assert_eq!(saved.stats.rounds, 1);
+        assert_eq!(saved.stats.busts, 1);
+        assert!(saved.preferences.show_odds);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_count_checks_disabled_by_default() {
+        let game = Game::new();
+        assert_eq!(game.count_check_chance, 0.0);
+
+        let game = Game::new().with_count_checks(0.25);
+        assert_eq!(game.count_check_chance, 0.25);
+    }
+
+    #[test]
+    fn test_resume_from_snapshot() {
+        let mut game = Game::new();
+        game.player_chips = 640;
+        game.deal_initial_cards();
+        let path =
+            std::env::temp_dir().join(format!("blackjack-cli-session-{}.json", std::process::id()));
+        game.save_path = Some(path.clone());
+        game.save_session().expect("saved");
+
+        let saved = SavedGame::load(&path).expect("readable").expect("exists");
+        let mut resumed = Game::new().with_saved_game(saved);
+        assert_eq!(resumed.player_chips, 640);
+        assert_eq!(resumed.running_count, game.running_count);
+        assert_eq!(resumed.deck.cards_remaining(), 48);
+        // The next cards come off the shoe in the same order
+        for _ in 0..48 {
+            assert_eq!(
+                resumed.deck.deal().map(|card| card.code()),
+                game.deck.deal().map(|card| card.code())
+            );
+        }
+
+        // Going broke removes the save rather than leaving a game that can't continue
+        game.player_chips = 0;
+        game.save_session().expect("removed");
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_round_record() {
+        let mut game = Game::new();
+        game.current_bet = 50;
+        game.deal_initial_cards();
+        assert_eq!(game.shoe_position, 0);
+        game.actions.push(PlayerAction {
+            action: Action::Stand,
+            card: None,
+        });
+        game.dealer_turn();
+        let (outcome, delta) = game.determine_winner();
+
+        let record = game.round_record(3, 1, outcome, delta);
+        assert_eq!(record.round, 3);
+        assert_eq!(record.seed, game.shoe_seed);
+        assert_eq!(record.player_cards.len(), 2);
+        assert_eq!(record.dealer_cards.len(), 2);
+        assert_eq!(
+            record.dealer_draws.len(),
+            game.dealer_hand.cards().len() - 2
+        );
+        assert_eq!(record.chips as i64, 1000 + delta);
+
+        // The seed rebuilds the shoe the round was dealt from
+        let mut shoe = Deck::new();
+        shoe.shuffle_seeded(record.seed.unwrap());
+        assert_eq!(
+            shoe.deal().map(|card| card.code()),
+            Some(record.player_cards[0].code())
+        );
+        let mut shoe = Deck::new();
+        shoe.shuffle_seeded(record.seed.unwrap());
+        assert_eq!(record.shoe_hash, Some(deck::order_hash(shoe.upcoming())));
+    }
 }
@@ -1,9 +1,13 @@
 use std::fmt;
 
+use serde::{Deserialize, Serialize};
+
 use crate::card::{Card, Rank};
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Hand {
     cards: Vec<Card>,
+    from_split: bool,
 }
 
 impl Default for Hand {
@@ -14,14 +18,29 @@ impl Default for Hand {
 
 impl Hand {
     pub fn new() -> Self {
-        Hand { cards: Vec::new() }
+        Hand {
+            cards: Vec::new(),
+            from_split: false,
+        }
+    }
+
+    // A two-card 21 on a split hand counts as 21, not blackjack
+    pub fn from_split(card: Card) -> Self {
+        Hand {
+            cards: vec![card],
+            from_split: true,
+        }
+    }
+
+    pub fn is_from_split(&self) -> bool {
+        self.from_split
     }
 
     pub fn add_card(&mut self, card: Card) {
         self.cards.push(card);
     }
 
-    pub fn value(&self) -> u8 {
+    fn total_and_soft_aces(&self) -> (u8, u8) {
         let mut total = 0;
         let mut aces = 0;
 
@@ -37,7 +56,19 @@ impl Hand {
             aces -= 1;
         }
 
-        total
+        (total, aces)
+    }
+
+    pub fn value(&self) -> u8 {
+        self.total_and_soft_aces().0
+    }
+
+    pub fn is_soft(&self) -> bool {
+        self.total_and_soft_aces().1 > 0
+    }
+
+    pub fn is_pair(&self) -> bool {
+        self.cards.len() == 2 && self.cards[0].value() == self.cards[1].value()
     }
 
     pub fn is_busted(&self) -> bool {
@@ -45,11 +76,12 @@ impl Hand {
     }
 
     pub fn is_blackjack(&self) -> bool {
-        self.cards.len() == 2 && self.value() == 21
+        !self.from_split && self.cards.len() == 2 && self.value() == 21
     }
 
     pub fn clear(&mut self) {
         self.cards.clear();
+        self.from_split = false;
     }
 
     pub fn cards(&self) -> &[Card] {
@@ -163,6 +195,49 @@ mod tests {
         assert_eq!(hand.value(), 21); // Both Aces as 1
     }
 
+    #[test]
+    fn test_is_soft() {
+        let mut hand = Hand::new();
+        hand.add_card(Card {
+            suit: Suit::Hearts,
+            rank: Rank::Ace,
+        });
+        hand.add_card(Card {
+            suit: Suit::Diamonds,
+            rank: Rank::Six,
+        });
+
+        assert!(hand.is_soft()); // Soft 17
+
+        hand.add_card(Card {
+            suit: Suit::Clubs,
+            rank: Rank::Nine,
+        });
+        assert_eq!(hand.value(), 16);
+        assert!(!hand.is_soft()); // Ace now has to count as 1
+    }
+
+    #[test]
+    fn test_is_pair() {
+        let mut hand = Hand::new();
+        hand.add_card(Card {
+            suit: Suit::Hearts,
+            rank: Rank::King,
+        });
+        hand.add_card(Card {
+            suit: Suit::Diamonds,
+            rank: Rank::Ten,
+        });
+
+        assert!(hand.is_pair()); // Ten-value cards pair up
+
+        hand.add_card(Card {
+            suit: Suit::Clubs,
+            rank: Rank::Ten,
+        });
+        assert!(!hand.is_pair());
+    }
+
     #[test]
     fn test_is_busted() {
         let mut hand = Hand::new();
@@ -206,6 +281,22 @@ mod tests {
         assert!(!hand.is_blackjack());
     }
 
+    #[test]
+    fn test_split_hand_is_not_blackjack() {
+        let mut hand = Hand::from_split(Card {
+            suit: Suit::Hearts,
+            rank: Rank::Ace,
+        });
+        hand.add_card(Card {
+            suit: Suit::Diamonds,
+            rank: Rank::King,
+        });
+
+        assert!(hand.is_from_split());
+        assert_eq!(hand.value(), 21);
+        assert!(!hand.is_blackjack());
+    }
+
     #[test]
     fn test_not_blackjack_with_21_in_3_cards() {
         let mut hand = Hand::new();
@@ -0,0 +1,306 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::card::Card;
+use crate::outcome::Outcome;
+use crate::profile;
+use crate::rules::Rules;
+use crate::strategy::Action;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PlayerAction {
+    pub action: Action,
+    // The card drawn, for actions that take one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub card: Option<Card>,
+}
+
+// One line of the history file. Dealer cards are in deal order, so the hole card
+// comes first and the upcard second.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoundRecord {
+    pub round: u64,
+    // When the game writing this round started, so replays know where chips carry over
+    #[serde(default)]
+    pub session: u64,
+    pub timestamp: u64,
+    // Seed the shoe was shuffled with, and how many cards had been dealt from it
+    pub seed: Option<u64>,
+    pub shoe_position: usize,
+    // Hash of the shoe's order from this round on, so a rebuilt shoe can be checked
+    // beyond the cards the round used
+    #[serde(default)]
+    pub shoe_hash: Option<u64>,
+    pub rules: Rules,
+    pub bet: u32,
+    pub player_cards: Vec<Card>,
+    pub dealer_cards: Vec<Card>,
+    pub actions: Vec<PlayerAction>,
+    pub dealer_draws: Vec<Card>,
+    pub outcome: Outcome,
+    pub chip_delta: i64,
+    pub chips: u32,
+}
+
+impl RoundRecord {
+    // Every card the round took from the shoe, in the order they were dealt
+    pub fn dealt_cards(&self) -> impl Iterator<Item = Card> + '_ {
+        let (player, dealer) = (&self.player_cards, &self.dealer_cards);
+        [player.first(), dealer.first(), player.get(1), dealer.get(1)]
+            .into_iter()
+            .flatten()
+            .copied()
+            .chain(self.actions.iter().filter_map(|action| action.card))
+            .chain(self.dealer_draws.iter().copied())
+    }
+}
+
+pub fn timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
+
+// Each profile has its own history; playing without one uses "guest"
+pub fn default_path(profile: Option<&str>) -> PathBuf {
+    profile::data_dir()
+        .join("history")
+        .join(format!("{}.jsonl", profile.unwrap_or("guest")))
+}
+
+// The history a command reads: a file given on the command line, or a profile's history
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HistorySource {
+    pub path: Option<PathBuf>,
+    pub profile: Option<String>,
+}
+
+impl HistorySource {
+    // Takes FILE or --profile NAME, and returns Ok(false) for any other option
+    pub fn apply_option<'a>(
+        &mut self,
+        arg: &str,
+        rest: &mut impl Iterator<Item = &'a String>,
+    ) -> Result<bool, String> {
+        match arg {
+            "--profile" => {
+                let name = rest.next().ok_or("Missing value for --profile")?;
+                if !profile::valid_name(name) {
+                    return Err(format!("Invalid profile name: {name}"));
+                }
+                self.profile = Some(name.clone());
+            }
+            _ if arg.starts_with("--") => return Ok(false),
+            _ if self.path.is_none() => self.path = Some(PathBuf::from(arg)),
+            _ => return Err(format!("Unexpected argument: {arg}")),
+        }
+        Ok(true)
+    }
+
+    pub fn path(&self) -> PathBuf {
+        self.path
+            .clone()
+            .unwrap_or_else(|| default_path(self.profile.as_deref()))
+    }
+}
+
+pub fn read(path: &Path) -> Result<Vec<RoundRecord>, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|err| format!("Couldn't read {}: {err}", path.display()))?;
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(number, line)| {
+            serde_json::from_str(line)
+                .map_err(|err| format!("{}:{}: {err}", path.display(), number + 1))
+        })
+        .collect()
+}
+
+// An append-only JSON Lines file with one record per round
+pub struct HandHistory {
+    path: PathBuf,
+    rounds: u64,
+    session: u64,
+}
+
+impl HandHistory {
+    // Round numbers carry on from the records already in the file
+    pub fn open(path: PathBuf) -> Result<HandHistory, String> {
+        let rounds = match fs::read_to_string(&path) {
+            Ok(text) => text.lines().filter(|line| !line.trim().is_empty()).count() as u64,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => 0,
+            Err(err) => return Err(format!("Couldn't read {}: {err}", path.display())),
+        };
+        Ok(HandHistory {
+            path,
+            rounds,
+            session: timestamp(),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn next_round(&self) -> u64 {
+        self.rounds + 1
+    }
+
+    pub fn session(&self) -> u64 {
+        self.session
+    }
+
+    pub fn append(&mut self, record: &RoundRecord) -> Result<(), String> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|err| format!("Couldn't create {}: {err}", parent.display()))?;
+        }
+        let mut line = serde_json::to_string(record).map_err(|err| err.to_string())?;
+        line.push('\n');
+
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .map_err(|err| format!("Couldn't write {}: {err}", self.path.display()))?;
+        self.rounds += 1;
+        Ok(())
+    }
+}
+
+// Records for the tests of everything that reads the history
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use super::*;
+
+    pub(crate) fn cards(codes: &[&str]) -> Vec<Card> {
+        codes
+            .iter()
+            .map(|code| Card::from_code(code).unwrap())
+            .collect()
+    }
+
+    // Hits for each card given, then stands
+    pub(crate) fn hits(codes: &[&str]) -> Vec<PlayerAction> {
+        let mut actions: Vec<PlayerAction> = codes
+            .iter()
+            .map(|code| PlayerAction {
+                action: Action::Hit,
+                card: Card::from_code(code),
+            })
+            .collect();
+        actions.push(PlayerAction {
+            action: Action::Stand,
+            card: None,
+        });
+        actions
+    }
+
+    // 14 hits to 19, and the dealer's 15 busts on a king. Change fields with ..record()
+    pub(crate) fn record() -> RoundRecord {
+        RoundRecord {
+            round: 1,
+            session: 1,
+            timestamp: 0,
+            seed: None,
+            shoe_position: 0,
+            shoe_hash: None,
+            rules: Rules::single_deck(),
+            bet: 10,
+            player_cards: cards(&["TH", "4C"]),
+            dealer_cards: cards(&["9S", "6D"]),
+            actions: hits(&["5S"]),
+            dealer_draws: cards(&["KH"]),
+            outcome: Outcome::DealerBust,
+            chip_delta: 10,
+            chips: 1010,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fixtures::record;
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    fn source(args: &[String]) -> Result<HistorySource, String> {
+        let mut source = HistorySource::default();
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            if !source.apply_option(arg, &mut iter)? {
+                return Err(format!("Unknown option: {arg}"));
+            }
+        }
+        Ok(source)
+    }
+
+    #[test]
+    fn test_history_source() {
+        let from_file = source(&args(&["rounds.jsonl"])).unwrap();
+        assert_eq!(from_file.path(), PathBuf::from("rounds.jsonl"));
+
+        let from_profile = source(&args(&["--profile", "alice"])).unwrap();
+        assert_eq!(from_profile.profile.as_deref(), Some("alice"));
+        assert!(from_profile.path().ends_with("history/alice.jsonl"));
+
+        assert!(source(&args(&["a.jsonl", "b.jsonl"])).is_err());
+        assert!(source(&args(&["--profile", "../etc"])).is_err());
+        assert!(source(&args(&["--profile"])).is_err());
+        assert!(source(&args(&["--svg", "out.svg"])).is_err());
+    }
+
+    #[test]
+    fn test_appends_one_line_per_round() {
+        let path = std::env::temp_dir().join(format!(
+            "blackjack-cli-history-{}.jsonl",
+            std::process::id()
+        ));
+        let _ = fs::remove_file(&path);
+
+        let mut history = HandHistory::open(path.clone()).expect("opened");
+        assert_eq!(history.next_round(), 1);
+        history
+            .append(&RoundRecord {
+                timestamp: timestamp(),
+                ..record()
+            })
+            .expect("written");
+        history
+            .append(&RoundRecord {
+                round: 2,
+                ..record()
+            })
+            .expect("written");
+
+        // Reopening carries on the round numbers
+        let reopened = HandHistory::open(path.clone()).expect("opened");
+        assert_eq!(reopened.next_round(), 3);
+
+        let text = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains(r#""outcome":"dealer_bust""#));
+        assert!(lines[0].contains(r#"{"action":"hit","card":"5S"},{"action":"stand"}"#));
+
+        let records = read(&path).expect("valid records");
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].round, 2);
+        assert_eq!(records[1].dealer_draws[0].code(), "KH");
+
+        fs::write(&path, "{\"round\": 1}\n").unwrap();
+        let err = read(&path).err().unwrap();
+        assert!(err.contains(":1:"), "{err}");
+        let _ = fs::remove_file(&path);
+    }
+}
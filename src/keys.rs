@@ -0,0 +1,118 @@
+use std::io;
+use std::panic;
+use std::sync::Once;
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::terminal;
+
+pub const BET_STEP: u32 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Char(char),
+    Up,
+    Down,
+    Left,
+    Right,
+    Enter,
+    Backspace,
+    Esc,
+    Interrupt,
+}
+
+impl Key {
+    fn from_event(code: KeyCode, modifiers: KeyModifiers) -> Option<Key> {
+        let key = match code {
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => Key::Interrupt,
+            KeyCode::Char(c) => Key::Char(c.to_ascii_lowercase()),
+            KeyCode::Up => Key::Up,
+            KeyCode::Down => Key::Down,
+            KeyCode::Left => Key::Left,
+            KeyCode::Right => Key::Right,
+            KeyCode::Enter => Key::Enter,
+            KeyCode::Backspace => Key::Backspace,
+            KeyCode::Esc => Key::Esc,
+            _ => return None,
+        };
+        Some(key)
+    }
+}
+
+// Raw mode stays on only while a key is read, so everything the game prints
+// still goes through the normal cooked terminal
+struct RawMode;
+
+impl RawMode {
+    fn enable() -> io::Result<RawMode> {
+        static PANIC_HOOK: Once = Once::new();
+        PANIC_HOOK.call_once(|| {
+            let previous = panic::take_hook();
+            panic::set_hook(Box::new(move |info| {
+                let _ = terminal::disable_raw_mode();
+                previous(info);
+            }));
+        });
+        terminal::enable_raw_mode()?;
+        Ok(RawMode)
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+pub fn read_key() -> io::Result<Key> {
+    let _raw = RawMode::enable()?;
+    loop {
+        if let Event::Key(event) = event::read()? {
+            if event.kind != KeyEventKind::Press {
+                continue;
+            }
+            if let Some(key) = Key::from_event(event.code, event.modifiers) {
+                return Ok(key);
+            }
+        }
+    }
+}
+
+// Up and right raise the bet, down and left lower it, staying between 1 and
+// the chips available
+pub fn adjust_bet(bet: u32, key: Key, chips: u32) -> u32 {
+    let bet = match key {
+        Key::Up | Key::Right => bet.saturating_add(BET_STEP),
+        Key::Down | Key::Left => bet.saturating_sub(BET_STEP),
+        _ => bet,
+    };
+    bet.clamp(1, chips.max(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_from_event() {
+        let none = KeyModifiers::NONE;
+        assert_eq!(
+            Key::from_event(KeyCode::Char('H'), none),
+            Some(Key::Char('h'))
+        );
+        assert_eq!(Key::from_event(KeyCode::Up, none), Some(Key::Up));
+        assert_eq!(
+            Key::from_event(KeyCode::Char('c'), KeyModifiers::CONTROL),
+            Some(Key::Interrupt)
+        );
+        assert_eq!(Key::from_event(KeyCode::F(1), none), None);
+    }
+
+    #[test]
+    fn test_adjust_bet() {
+        assert_eq!(adjust_bet(10, Key::Up, 100), 15);
+        assert_eq!(adjust_bet(10, Key::Left, 100), 5);
+        assert_eq!(adjust_bet(3, Key::Down, 100), 1);
+        assert_eq!(adjust_bet(98, Key::Right, 100), 100);
+        assert_eq!(adjust_bet(10, Key::Enter, 100), 10);
+    }
+}
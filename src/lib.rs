@@ -1,5 +1,30 @@
+pub mod analysis;
+pub mod bankroll;
+pub mod betting;
 pub mod card;
+pub mod chart;
+pub mod color;
+pub mod count;
 pub mod deck;
 pub mod display;
+pub mod drill;
+pub mod edge;
+pub mod ev;
+pub mod export;
+pub mod fastsim;
 pub mod game;
 pub mod hand;
+pub mod history;
+pub mod keys;
+pub mod outcome;
+pub mod plot;
+pub mod profile;
+pub mod replay;
+pub mod review;
+pub mod rules;
+pub mod save;
+pub mod sim;
+pub mod stats;
+pub mod strategy;
+pub mod tui;
+pub mod whatif;
@@ -1,12 +1,403 @@
-mod card;
-mod deck;
-mod display;
-mod game;
-mod hand;
+use std::env;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+use std::process;
 
-use game::Game;
+use blackjack_cli::analysis::{self, ShoeComposition};
+use blackjack_cli::betting::BettingSystem;
+use blackjack_cli::chart::{self, ChartOptions};
+use blackjack_cli::color::{self, Theme};
+use blackjack_cli::display::{self, CardArt};
+use blackjack_cli::drill::{self, CountdownOptions};
+use blackjack_cli::edge::{EdgeOptions, HouseEdge};
+use blackjack_cli::ev;
+use blackjack_cli::export::{self, ExportOptions};
+use blackjack_cli::game::Game;
+use blackjack_cli::history::{self, HandHistory};
+use blackjack_cli::plot::{self, PlotOptions};
+use blackjack_cli::profile::{self, Profile, ProfileStore};
+use blackjack_cli::replay::{self, ReplayOptions};
+use blackjack_cli::rules::Rules;
+use blackjack_cli::save::{self, SavedGame};
+use blackjack_cli::strategy::{IndexTable, Strategy, DEFAULT_INDEX_TABLE};
+use blackjack_cli::tui;
 
-fn main() {
+const COUNT_CHECK_CHANCE: f64 = 0.2;
+const DEFAULT_BASE_BET: u32 = 10;
+
+const USAGE: &str = "\
+Usage:
+  blackjack-cli [--profile NAME] [--resume] [--[no-]count-checks] [--[no-]odds] [--[no-]review]
+                [--indices FILE] [--betting SYSTEM [--base-bet N]] [--history FILE] [--tui | --keys]
+                [--theme NAME] [--[no-]suit-letters] [--cards box|large|ascii|compact]
+  blackjack-cli stats [--profile NAME]
+  blackjack-cli bankroll [FILE | --profile NAME] [--svg FILE] [--width N] [--height N]
+  blackjack-cli export [FILE | --profile NAME] [--dir DIR]
+  blackjack-cli replay [FILE | --profile NAME] [--check]
+  blackjack-cli drill [--group 1|2|3] [--speed MS]
+  blackjack-cli indices
+  blackjack-cli dealer [rule options]
+  blackjack-cli ev CARDS... vs UPCARD [rule options]
+  blackjack-cli edge [--preset NAME] [--simulate HANDS] [rule options]
+  blackjack-cli chart [--format grid|csv|markdown] [--output FILE] [--theme NAME]
+                      [rule options]";
+
+fn exit_with_usage(message: &str) -> ! {
+    eprintln!("{message}");
+    eprintln!("{USAGE}");
+    process::exit(2);
+}
+
+fn load_profile(name: Option<String>) -> Option<(Profile, ProfileStore)> {
+    let store = ProfileStore::default_location();
+    let name = name.or_else(|| profile::choose_profile(&store))?;
+    let mut profile = store.load_or_create(&name).unwrap_or_else(|err| {
+        eprintln!("{err}");
+        process::exit(1);
+    });
+
+    if profile.stats.rounds == 0 && profile.bankroll == profile::STARTING_CHIPS {
+        println!("Created profile {name}.");
+    } else {
+        println!("Welcome back, {}", profile.summary());
+    }
+    if profile.rebuy_if_broke() {
+        println!(
+            "You were out of chips, so you start again with {}.",
+            profile.bankroll
+        );
+    }
+    Some((profile, store))
+}
+
+fn print_stats(args: &[String]) {
+    let store = ProfileStore::default_location();
+    let name = match args {
+        [] => profile::choose_profile(&store),
+        [flag, name] if flag == "--profile" => Some(name.clone()),
+        _ => exit_with_usage("Usage: blackjack-cli stats [--profile NAME]"),
+    };
+    let Some(name) = name else {
+        println!("Statistics are only kept for profiles. Play with --profile NAME to start one.");
+        return;
+    };
+
+    match store.load(&name) {
+        Ok(Some(profile)) => println!("{}", profile.stats.render(&format!("Lifetime: {name}"))),
+        Ok(None) => {
+            eprintln!("No profile called {name}");
+            process::exit(1);
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            process::exit(1);
+        }
+    }
+}
+
+fn play(args: &[String]) {
     let mut game = Game::new();
-    game.run();
+    // Left unset unless given, so a profile's saved preference is only replaced on request
+    let mut count_checks: Option<bool> = None;
+    let mut odds: Option<bool> = None;
+    let mut review: Option<bool> = None;
+    let mut betting: Option<String> = None;
+    let mut base_bet = None;
+    let mut profile_name = None;
+    let mut resume = false;
+    let mut history_path = None;
+    let mut full_screen = false;
+    let mut single_key = false;
+    let mut theme: Option<String> = None;
+    let mut suit_letters: Option<bool> = None;
+    let mut card_art: Option<String> = None;
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--count-checks" => count_checks = Some(true),
+            "--no-count-checks" => count_checks = Some(false),
+            "--odds" => odds = Some(true),
+            "--no-odds" => odds = Some(false),
+            "--review" => review = Some(true),
+            "--no-review" => review = Some(false),
+            "--resume" => resume = true,
+            "--tui" => full_screen = true,
+            "--keys" => single_key = true,
+            "--suit-letters" => suit_letters = Some(true),
+            "--no-suit-letters" => suit_letters = Some(false),
+            "--cards" => {
+                let name = iter
+                    .next()
+                    .unwrap_or_else(|| exit_with_usage("Missing value for --cards"));
+                if CardArt::parse(name).is_none() {
+                    exit_with_usage(&format!("Unknown card style: {name}"));
+                }
+                card_art = Some(name.clone());
+            }
+            "--theme" => {
+                let name = iter
+                    .next()
+                    .unwrap_or_else(|| exit_with_usage("Missing value for --theme"));
+                if Theme::parse(name).is_none() {
+                    let names: Vec<&str> = Theme::ALL.iter().map(Theme::name).collect();
+                    exit_with_usage(&format!(
+                        "Unknown theme: {name}. Choose from {}",
+                        names.join(", ")
+                    ));
+                }
+                theme = Some(name.clone());
+            }
+            "--betting" => {
+                let name = iter
+                    .next()
+                    .unwrap_or_else(|| exit_with_usage("Missing value for --betting"));
+                if BettingSystem::parse(name).is_none() {
+                    exit_with_usage(&format!("Unknown betting system: {name}"));
+                }
+                betting = Some(name.clone());
+            }
+            "--base-bet" => {
+                base_bet = match iter.next().map(|value| value.parse::<u32>()) {
+                    Some(Ok(bet)) if bet > 0 => Some(bet),
+                    _ => exit_with_usage("--base-bet needs a positive number of chips"),
+                }
+            }
+            "--history" => {
+                let path = iter
+                    .next()
+                    .unwrap_or_else(|| exit_with_usage("Missing value for --history"));
+                history_path = Some(PathBuf::from(path));
+            }
+            "--profile" => {
+                let name = iter
+                    .next()
+                    .unwrap_or_else(|| exit_with_usage("Missing value for --profile"));
+                if !profile::valid_name(name) {
+                    exit_with_usage("Profile names can only use letters, digits, - and _");
+                }
+                profile_name = Some(name.clone());
+            }
+            "--indices" => {
+                let path = iter
+                    .next()
+                    .unwrap_or_else(|| exit_with_usage("Missing value for --indices"));
+                let indices = IndexTable::load(Path::new(path)).unwrap_or_else(|err| {
+                    eprintln!("{err}");
+                    process::exit(1);
+                });
+                game = game.with_strategy(Strategy::with_indices(indices));
+            }
+            _ => exit_with_usage(&format!("Unknown argument: {arg}")),
+        }
+    }
+    if full_screen && single_key {
+        exit_with_usage("--tui and --keys can't be used together");
+    }
+
+    // Options given on the command line replace the profile's saved preferences
+    let profile = load_profile(profile_name);
+    let profile_name = profile.as_ref().map(|(profile, _)| profile.name.clone());
+    let save_path = save::default_path(profile_name.as_deref());
+    let history_path =
+        history_path.unwrap_or_else(|| history::default_path(profile_name.as_deref()));
+    if let Some((mut profile, store)) = profile {
+        let preferences = &mut profile.preferences;
+        count_checks = count_checks.or(Some(preferences.count_checks));
+        odds = odds.or(Some(preferences.show_odds));
+        review = review.or(Some(preferences.review));
+        if betting.is_some() {
+            preferences.betting = betting.clone();
+            preferences.base_bet = base_bet.or(preferences.base_bet);
+        } else {
+            betting = preferences.betting.clone();
+        }
+        base_bet = base_bet.or(preferences.base_bet);
+        if theme.is_some() {
+            preferences.theme = theme.clone();
+        } else {
+            theme = preferences.theme.clone();
+        }
+        match suit_letters {
+            Some(letters) => preferences.suit_letters = letters,
+            None => suit_letters = Some(preferences.suit_letters),
+        }
+        if card_art.is_some() {
+            preferences.card_art = card_art.clone();
+        } else {
+            card_art = preferences.card_art.clone();
+        }
+        game = game.with_profile(profile, store);
+    }
+
+    if let Some(theme) = theme.as_deref().and_then(Theme::parse) {
+        color::set_theme(theme);
+    }
+    display::set_suit_letters(suit_letters == Some(true));
+    if let Some(art) = card_art.as_deref().and_then(CardArt::parse) {
+        display::set_card_art(art);
+    }
+    if count_checks == Some(true) {
+        game = game.with_count_checks(COUNT_CHECK_CHANCE);
+    }
+    game = game
+        .with_odds(odds == Some(true))
+        .with_review(review == Some(true));
+    if let Some(system) = betting.as_deref().and_then(BettingSystem::parse) {
+        game = game.with_betting(system, base_bet.unwrap_or(DEFAULT_BASE_BET));
+    }
+
+    let saved = SavedGame::load(&save_path).unwrap_or_else(|err| {
+        eprintln!("{err}");
+        process::exit(1);
+    });
+    match (saved, resume) {
+        (Some(saved), true) => {
+            println!(
+                "Resuming with {} chips, {} cards left in the shoe and a running count of {:+}.",
+                saved.player_chips,
+                saved.deck.cards_remaining(),
+                saved.running_count.value()
+            );
+            game = game.with_saved_game(saved);
+        }
+        (None, true) => println!("No saved game to resume, so starting a new shoe."),
+        (Some(_), false) => {
+            println!("Starting a new shoe. Use --resume to carry on with your saved game instead.")
+        }
+        (None, false) => {}
+    }
+    let history = HandHistory::open(history_path).unwrap_or_else(|err| {
+        eprintln!("{err}");
+        process::exit(1);
+    });
+    if single_key && !io::stdin().is_terminal() {
+        println!("Single-key controls need a terminal, so reading whole lines instead.");
+        single_key = false;
+    }
+    let mut game = game
+        .with_save_file(save_path)
+        .with_history(history)
+        .with_single_key(single_key);
+    if full_screen {
+        if let Err(err) = tui::run(game) {
+            eprintln!("{err}");
+            process::exit(1);
+        }
+    } else {
+        game.run();
+    }
+}
+
+fn parse_rules(args: &[String]) -> Rules {
+    let mut rules = Rules::default();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match rules.apply_option(arg, &mut iter) {
+            Ok(true) => {}
+            Ok(false) => exit_with_usage(&format!("Unknown argument: {arg}")),
+            Err(err) => exit_with_usage(&err),
+        }
+    }
+    rules
+}
+
+fn print_dealer_outcomes(rules: &Rules) {
+    println!("Dealer outcomes from a full shoe ({rules})\n");
+    for upcard in 2..=11 {
+        let shoe = ShoeComposition::full(rules.decks).without_value(upcard);
+        let outcomes = analysis::dealer_outcomes(upcard, &shoe, rules);
+        let label = match upcard {
+            11 => "A".to_string(),
+            value => value.to_string(),
+        };
+        println!("{label:>2}  {outcomes}");
+    }
+}
+
+fn print_action_values(args: &[String]) {
+    let split = args
+        .iter()
+        .position(|arg| arg.starts_with("--"))
+        .unwrap_or(args.len());
+    let (hand, upcard) =
+        ev::parse_situation(&args[..split]).unwrap_or_else(|err| exit_with_usage(&err));
+    let rules = parse_rules(&args[split..]);
+
+    let shoe = ShoeComposition::full(rules.decks)
+        .without(hand.cards())
+        .without(&[upcard]);
+    let values = ev::action_values(
+        &hand,
+        &upcard,
+        &shoe,
+        &rules,
+        ev::available_for(&hand, &rules),
+    );
+
+    let cards: Vec<String> = hand.cards().iter().map(|card| card.to_string()).collect();
+    println!("{} vs {upcard} ({rules})\n", cards.join(" "));
+    print!("{values}");
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    color::init();
+
+    match args.first().map(String::as_str) {
+        Some("drill") => {
+            let options =
+                CountdownOptions::from_args(&args[1..]).unwrap_or_else(|err| exit_with_usage(&err));
+            drill::run_countdown(&options);
+        }
+        Some("indices") => print!("{DEFAULT_INDEX_TABLE}"),
+        Some("dealer") => print_dealer_outcomes(&parse_rules(&args[1..])),
+        Some("ev") => print_action_values(&args[1..]),
+        Some("edge") => {
+            let options =
+                EdgeOptions::from_args(&args[1..]).unwrap_or_else(|err| exit_with_usage(&err));
+            let mut edge = HouseEdge::estimate(&options.rules);
+            if let Some(hands) = options.simulate {
+                edge = edge.with_simulation(hands, rand::random());
+            }
+            println!("{edge}");
+        }
+        Some("chart") => {
+            let options =
+                ChartOptions::from_args(&args[1..]).unwrap_or_else(|err| exit_with_usage(&err));
+            if let Some(theme) = options.theme {
+                color::set_theme(theme);
+            }
+            if let Err(err) = chart::run(&options) {
+                eprintln!("{err}");
+                process::exit(1);
+            }
+        }
+        Some("stats") => print_stats(&args[1..]),
+        Some("bankroll") => {
+            let options =
+                PlotOptions::from_args(&args[1..]).unwrap_or_else(|err| exit_with_usage(&err));
+            if let Err(err) = plot::run(&options) {
+                eprintln!("{err}");
+                process::exit(1);
+            }
+        }
+        Some("export") => {
+            let options =
+                ExportOptions::from_args(&args[1..]).unwrap_or_else(|err| exit_with_usage(&err));
+            if let Err(err) = export::run(&options) {
+                eprintln!("{err}");
+                process::exit(1);
+            }
+        }
+        Some("replay") => {
+            let options =
+                ReplayOptions::from_args(&args[1..]).unwrap_or_else(|err| exit_with_usage(&err));
+            if let Err(err) = replay::run(&options) {
+                eprintln!("{err}");
+                process::exit(1);
+            }
+        }
+        _ => play(&args),
+    }
 }
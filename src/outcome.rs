@@ -0,0 +1,138 @@
+use serde::{Deserialize, Serialize};
+
+use crate::hand::Hand;
+use crate::rules::Rules;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    PlayerBust,
+    DealerBust,
+    PlayerBlackjack,
+    DealerBlackjack,
+    PlayerWins,
+    DealerWins,
+    Push,
+}
+
+impl Outcome {
+    pub fn of(player: &Hand, dealer: &Hand) -> Outcome {
+        Outcome::from_totals(
+            player.value(),
+            player.is_blackjack(),
+            dealer.value(),
+            dealer.is_blackjack(),
+        )
+    }
+
+    pub fn from_totals(
+        player_value: u8,
+        player_blackjack: bool,
+        dealer_value: u8,
+        dealer_blackjack: bool,
+    ) -> Outcome {
+        if player_value > 21 {
+            Outcome::PlayerBust
+        } else if player_blackjack && dealer_blackjack {
+            Outcome::Push
+        } else if player_blackjack {
+            Outcome::PlayerBlackjack
+        } else if dealer_blackjack {
+            Outcome::DealerBlackjack
+        } else if dealer_value > 21 {
+            Outcome::DealerBust
+        } else if player_value > dealer_value {
+            Outcome::PlayerWins
+        } else if dealer_value > player_value {
+            Outcome::DealerWins
+        } else {
+            Outcome::Push
+        }
+    }
+
+    pub fn message(&self) -> &'static str {
+        match self {
+            Outcome::PlayerBust => "You lose! You busted.",
+            Outcome::DealerBust => "You win! Dealer busted.",
+            Outcome::PlayerBlackjack => "You win with Blackjack!",
+            Outcome::DealerBlackjack => "Dealer wins with Blackjack!",
+            Outcome::PlayerWins => "You win!",
+            Outcome::DealerWins => "Dealer wins!",
+            Outcome::Push => "Push! It's a tie.",
+        }
+    }
+
+    pub fn chip_delta(&self, bet: u32, rules: &Rules) -> i64 {
+        match self {
+            Outcome::PlayerBlackjack => rules.blackjack_winnings(bet) as i64,
+            Outcome::DealerBust | Outcome::PlayerWins => bet as i64,
+            Outcome::PlayerBust | Outcome::DealerBlackjack | Outcome::DealerWins => -(bet as i64),
+            Outcome::Push => 0,
+        }
+    }
+
+    // Result in units of the bet, for simulations that don't deal in whole chips
+    pub fn units(&self, rules: &Rules) -> f64 {
+        match self {
+            Outcome::PlayerBlackjack => rules.blackjack_ratio(),
+            Outcome::DealerBust | Outcome::PlayerWins => 1.0,
+            Outcome::PlayerBust | Outcome::DealerBlackjack | Outcome::DealerWins => -1.0,
+            Outcome::Push => 0.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::{Card, Rank, Suit};
+
+    fn hand(ranks: &[Rank]) -> Hand {
+        let mut hand = Hand::new();
+        for &rank in ranks {
+            hand.add_card(Card {
+                suit: Suit::Hearts,
+                rank,
+            });
+        }
+        hand
+    }
+
+    #[test]
+    fn test_outcome_of() {
+        let blackjack = hand(&[Rank::Ace, Rank::King]);
+        let twenty = hand(&[Rank::King, Rank::Queen]);
+        let nineteen = hand(&[Rank::King, Rank::Nine]);
+        let bust = hand(&[Rank::King, Rank::Queen, Rank::Two]);
+
+        assert_eq!(Outcome::of(&bust, &bust), Outcome::PlayerBust);
+        assert_eq!(Outcome::of(&twenty, &bust), Outcome::DealerBust);
+        assert_eq!(Outcome::of(&blackjack, &twenty), Outcome::PlayerBlackjack);
+        assert_eq!(Outcome::of(&twenty, &blackjack), Outcome::DealerBlackjack);
+        assert_eq!(Outcome::of(&blackjack, &blackjack), Outcome::Push);
+        assert_eq!(Outcome::of(&twenty, &nineteen), Outcome::PlayerWins);
+        assert_eq!(Outcome::of(&nineteen, &twenty), Outcome::DealerWins);
+        assert_eq!(Outcome::of(&twenty, &twenty), Outcome::Push);
+    }
+
+    #[test]
+    fn test_blackjack_beats_dealer_bust() {
+        let blackjack = hand(&[Rank::Ace, Rank::King]);
+        let bust = hand(&[Rank::King, Rank::Six, Rank::Nine]);
+
+        // A natural is paid at the blackjack rate even if the dealer goes on to bust
+        assert_eq!(Outcome::of(&blackjack, &bust), Outcome::PlayerBlackjack);
+    }
+
+    #[test]
+    fn test_payouts() {
+        let rules = Rules::default();
+        assert_eq!(Outcome::PlayerBlackjack.chip_delta(10, &rules), 15);
+        assert_eq!(Outcome::PlayerWins.chip_delta(10, &rules), 10);
+        assert_eq!(Outcome::DealerBlackjack.chip_delta(10, &rules), -10);
+        assert_eq!(Outcome::Push.chip_delta(10, &rules), 0);
+
+        assert_eq!(Outcome::PlayerBlackjack.units(&rules), 1.5);
+        assert_eq!(Outcome::PlayerBust.units(&rules), -1.0);
+    }
+}
@@ -0,0 +1,477 @@
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::history::{self, HistorySource, RoundRecord};
+
+// Dot bits of a braille character, by row and then column
+const BRAILLE_DOTS: [[u8; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+pub const DEFAULT_WIDTH: usize = 60;
+pub const DEFAULT_HEIGHT: usize = 12;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Drawdown {
+    pub peak: usize,
+    pub trough: usize,
+    pub amount: i64,
+}
+
+// Chip balance after each round, starting with the balance before the first
+#[derive(Debug, Clone, PartialEq)]
+pub struct BalanceSeries {
+    pub balances: Vec<i64>,
+}
+
+impl BalanceSeries {
+    pub fn new(balances: Vec<i64>) -> Self {
+        BalanceSeries { balances }
+    }
+
+    pub fn from_records(records: &[RoundRecord]) -> Self {
+        let start = records
+            .first()
+            .map(|first| first.chips as i64 - first.chip_delta);
+        let balances = start
+            .into_iter()
+            .chain(records.iter().map(|record| record.chips as i64))
+            .collect();
+        BalanceSeries { balances }
+    }
+
+    pub fn high_water_marks(&self) -> Vec<i64> {
+        let mut peak = i64::MIN;
+        self.balances
+            .iter()
+            .map(|&balance| {
+                peak = peak.max(balance);
+                peak
+            })
+            .collect()
+    }
+
+    // The highest balance and the first round it was reached
+    pub fn high_water_mark(&self) -> Option<(usize, i64)> {
+        let peak = *self.balances.iter().max()?;
+        let round = self.balances.iter().position(|&b| b == peak)?;
+        Some((round, peak))
+    }
+
+    // The largest fall from a high-water mark to a later low
+    pub fn max_drawdown(&self) -> Option<Drawdown> {
+        let mut peak = 0;
+        let mut worst: Option<Drawdown> = None;
+        for (i, &balance) in self.balances.iter().enumerate() {
+            if balance > self.balances[peak] {
+                peak = i;
+            }
+            let amount = self.balances[peak] - balance;
+            if amount > 0 && worst.is_none_or(|worst| amount > worst.amount) {
+                worst = Some(Drawdown {
+                    peak,
+                    trough: i,
+                    amount,
+                });
+            }
+        }
+        worst
+    }
+
+    fn range(&self) -> (i64, i64) {
+        let min = self.balances.iter().copied().min().unwrap_or(0);
+        let max = self.balances.iter().copied().max().unwrap_or(0);
+        if min == max {
+            (min - 1, max + 1)
+        } else {
+            (min, max)
+        }
+    }
+
+    // A braille line chart with the high-water mark dotted above the balance, and
+    // the worst drawdown bracketed under the axis
+    pub fn render_terminal(&self, width: usize, height: usize) -> String {
+        if self.balances.len() < 2 {
+            return "Play at least one round to see a bankroll chart.".to_string();
+        }
+        let width = width.max(10);
+        let height = height.max(3);
+        let (min, max) = self.range();
+        let mut canvas = BrailleCanvas::new(width, height);
+
+        let last = self.balances.len() - 1;
+        let (dot_width, dot_height) = (canvas.dot_width(), canvas.dot_height());
+        let x = |round: usize| round * (dot_width - 1) / last;
+        let y = |balance: i64| {
+            let rows = (dot_height - 1) as f64;
+            ((max - balance) as f64 / (max - min) as f64 * rows).round() as usize
+        };
+
+        // Dotted high-water mark, drawn wherever it sits above the balance
+        let marks = self.high_water_marks();
+        for dot in 0..dot_width {
+            let round = ((dot * last + dot_width / 2) / (dot_width - 1)).min(last);
+            if dot % 2 == 0 && marks[round] > self.balances[round] {
+                canvas.set(dot, y(marks[round]));
+            }
+        }
+        for round in 1..=last {
+            canvas.line(
+                (x(round - 1), y(self.balances[round - 1])),
+                (x(round), y(self.balances[round])),
+            );
+        }
+
+        let labels = [max.to_string(), min.to_string()];
+        let label_width = labels.iter().map(String::len).max().unwrap_or(1);
+        let mut text = String::new();
+        for (row, line) in canvas.rows().into_iter().enumerate() {
+            let label = match row {
+                0 => &labels[0],
+                row if row == height - 1 => &labels[1],
+                _ => "",
+            };
+            let _ = writeln!(text, "{label:>label_width$} ┤{line}");
+        }
+        let _ = writeln!(text, "{:label_width$} └{}", "", "─".repeat(width));
+        let end = format!("round {last}");
+        let _ = writeln!(
+            text,
+            "{:label_width$}  0{end:>pad$}",
+            "",
+            pad = width.saturating_sub(1)
+        );
+
+        if let Some(drawdown) = self.max_drawdown() {
+            let start = x(drawdown.peak) / 2;
+            let end = (x(drawdown.trough) / 2).max(start + 1);
+            let _ = writeln!(
+                text,
+                "{:label_width$}  {}╰{}╯",
+                "",
+                " ".repeat(start),
+                "─".repeat(end - start - 1)
+            );
+        }
+
+        if let Some((round, peak)) = self.high_water_mark() {
+            let _ = writeln!(
+                text,
+                "High-water mark: {peak} chips (round {round}), dotted while the balance is below it"
+            );
+        }
+        match self.max_drawdown() {
+            Some(drawdown) => {
+                let _ = write!(
+                    text,
+                    "Max drawdown: -{} chips, from round {} to round {} (bracketed under the axis)",
+                    drawdown.amount, drawdown.peak, drawdown.trough
+                );
+            }
+            None => text.push_str("No drawdowns: every round ended at a new high."),
+        }
+        text
+    }
+
+    // A standalone SVG with the balance line, the high-water mark as a dashed line,
+    // every drawdown shaded beneath it and the worst one outlined
+    pub fn render_svg(&self, width: u32, height: u32) -> String {
+        let (min, max) = self.range();
+        let (left, right, top, bottom) = (60.0, 20.0, 30.0, 40.0);
+        let plot_width = width as f64 - left - right;
+        let plot_height = height as f64 - top - bottom;
+        let last = self.balances.len().saturating_sub(1).max(1) as f64;
+        let x = |round: usize| left + round as f64 / last * plot_width;
+        let y = |balance: i64| top + (max - balance) as f64 / (max - min) as f64 * plot_height;
+        let points = |values: &[i64]| {
+            let points: Vec<String> = values
+                .iter()
+                .enumerate()
+                .map(|(i, &v)| format!("{:.1},{:.1}", x(i), y(v)))
+                .collect();
+            points.join(" ")
+        };
+
+        let marks = self.high_water_marks();
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+viewBox=\"0 0 {width} {height}\" font-family=\"sans-serif\" font-size=\"12\">\n"
+        );
+        let _ = writeln!(
+            svg,
+            "  <rect width=\"{width}\" height=\"{height}\" fill=\"white\"/>"
+        );
+        let _ = writeln!(
+            svg,
+            "  <text x=\"{left}\" y=\"18\" font-size=\"14\">Chip balance per round</text>"
+        );
+
+        // Drawdowns fill the gap between the high-water mark and the balance
+        let reversed: Vec<String> = self
+            .balances
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(i, &v)| format!("{:.1},{:.1}", x(i), y(v)))
+            .collect();
+        let _ = writeln!(
+            svg,
+            "  <polygon points=\"{} {}\" fill=\"#d62728\" fill-opacity=\"0.2\" stroke=\"none\"/>",
+            points(&marks),
+            reversed.join(" ")
+        );
+        if let Some(drawdown) = self.max_drawdown() {
+            let _ = writeln!(
+                svg,
+                "  <rect x=\"{:.1}\" y=\"{top}\" width=\"{:.1}\" height=\"{plot_height}\" \
+fill=\"none\" stroke=\"#d62728\" stroke-dasharray=\"2 3\"><title>Max drawdown: -{} chips</title></rect>",
+                x(drawdown.peak),
+                x(drawdown.trough) - x(drawdown.peak),
+                drawdown.amount
+            );
+        }
+
+        let _ = writeln!(
+            svg,
+            "  <polyline points=\"{}\" fill=\"none\" stroke=\"#2ca02c\" stroke-dasharray=\"6 4\"/>",
+            points(&marks)
+        );
+        let _ = writeln!(
+            svg,
+            "  <polyline points=\"{}\" fill=\"none\" stroke=\"#1f77b4\" stroke-width=\"2\"/>",
+            points(&self.balances)
+        );
+
+        // Axes and labels
+        let _ = writeln!(
+            svg,
+            "  <path d=\"M{left},{top} V{:.1} H{:.1}\" fill=\"none\" stroke=\"black\"/>",
+            top + plot_height,
+            left + plot_width
+        );
+        for (value, anchor_y) in [(max, top), (min, top + plot_height)] {
+            let _ = writeln!(
+                svg,
+                "  <text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\">{value}</text>",
+                left - 6.0,
+                anchor_y + 4.0
+            );
+        }
+        let _ = writeln!(
+            svg,
+            "  <text x=\"{left}\" y=\"{:.1}\">0</text>\n  <text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\">round {}</text>",
+            top + plot_height + 16.0,
+            left + plot_width,
+            top + plot_height + 16.0,
+            self.balances.len().saturating_sub(1)
+        );
+        let legend_y = height as f64 - 8.0;
+        let mut legend = String::from("Blue: balance. Green dashes: high-water mark.");
+        if let Some(drawdown) = self.max_drawdown() {
+            let _ = write!(
+                legend,
+                " Red: drawdowns, the worst -{} chips from round {} to {}.",
+                drawdown.amount, drawdown.peak, drawdown.trough
+            );
+        }
+        let _ = writeln!(svg, "  <text x=\"{left}\" y=\"{legend_y}\">{legend}</text>");
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PlotOptions {
+    pub history: HistorySource,
+    pub svg: Option<PathBuf>,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Default for PlotOptions {
+    fn default() -> Self {
+        PlotOptions {
+            history: HistorySource::default(),
+            svg: None,
+            width: DEFAULT_WIDTH,
+            height: DEFAULT_HEIGHT,
+        }
+    }
+}
+
+impl PlotOptions {
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let mut options = PlotOptions::default();
+        let mut iter = args.iter();
+
+        while let Some(arg) = iter.next() {
+            if options.history.apply_option(arg, &mut iter)? {
+                continue;
+            }
+
+            let value = iter
+                .next()
+                .ok_or_else(|| format!("Missing value for {arg}"))?;
+            let size = || {
+                value
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|size| (3..=500).contains(size))
+                    .ok_or_else(|| format!("{arg} needs a number from 3 to 500"))
+            };
+            match arg.as_str() {
+                "--svg" => options.svg = Some(PathBuf::from(value)),
+                "--width" => options.width = size()?,
+                "--height" => options.height = size()?,
+                _ => return Err(format!("Unknown option: {arg}")),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+// Charts the bankroll over every round in a saved history
+pub fn run(options: &PlotOptions) -> Result<(), String> {
+    let series = BalanceSeries::from_records(&history::read(&options.history.path())?);
+    println!("{}", series.render_terminal(options.width, options.height));
+
+    if let Some(svg) = &options.svg {
+        fs::write(svg, series.render_svg(800, 400))
+            .map_err(|err| format!("Couldn't write {}: {err}", svg.display()))?;
+        println!("Saved the chart to {}", svg.display());
+    }
+    Ok(())
+}
+
+struct BrailleCanvas {
+    width: usize,
+    height: usize,
+    cells: Vec<u8>,
+}
+
+impl BrailleCanvas {
+    fn new(width: usize, height: usize) -> Self {
+        BrailleCanvas {
+            width,
+            height,
+            cells: vec![0; width * height],
+        }
+    }
+
+    fn dot_width(&self) -> usize {
+        self.width * 2
+    }
+
+    fn dot_height(&self) -> usize {
+        self.height * 4
+    }
+
+    fn set(&mut self, x: usize, y: usize) {
+        if x < self.dot_width() && y < self.dot_height() {
+            self.cells[(y / 4) * self.width + x / 2] |= BRAILLE_DOTS[y % 4][x % 2];
+        }
+    }
+
+    // Steps along the longer axis so the line has no gaps
+    fn line(&mut self, from: (usize, usize), to: (usize, usize)) {
+        let (x0, y0) = (from.0 as f64, from.1 as f64);
+        let (x1, y1) = (to.0 as f64, to.1 as f64);
+        let steps = (x1 - x0).abs().max((y1 - y0).abs()).max(1.0) as usize;
+        for step in 0..=steps {
+            let t = step as f64 / steps as f64;
+            let x = (x0 + (x1 - x0) * t).round() as usize;
+            let y = (y0 + (y1 - y0) * t).round() as usize;
+            self.set(x, y);
+        }
+    }
+
+    fn rows(&self) -> Vec<String> {
+        self.cells
+            .chunks(self.width)
+            .map(|row| {
+                row.iter()
+                    .map(|&bits| char::from_u32(0x2800 + bits as u32).unwrap_or(' '))
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::fixtures;
+
+    fn series() -> BalanceSeries {
+        BalanceSeries::new(vec![1000, 1050, 1100, 1020, 950, 1010, 1150, 1120])
+    }
+
+    #[test]
+    fn test_high_water_and_drawdown() {
+        let series = series();
+        assert_eq!(
+            series.high_water_marks(),
+            vec![1000, 1050, 1100, 1100, 1100, 1100, 1150, 1150]
+        );
+        assert_eq!(series.high_water_mark(), Some((6, 1150)));
+        assert_eq!(
+            series.max_drawdown(),
+            Some(Drawdown {
+                peak: 2,
+                trough: 4,
+                amount: 150
+            })
+        );
+        assert_eq!(BalanceSeries::new(vec![10, 20, 30]).max_drawdown(), None);
+    }
+
+    #[test]
+    fn test_braille_canvas() {
+        let mut canvas = BrailleCanvas::new(2, 1);
+        canvas.set(0, 0);
+        canvas.set(3, 3);
+        assert_eq!(canvas.rows(), vec!["⠁⢀"]);
+
+        let mut canvas = BrailleCanvas::new(1, 1);
+        canvas.line((0, 0), (0, 3));
+        assert_eq!(canvas.rows(), vec!["⡇"]);
+    }
+
+    #[test]
+    fn test_render_terminal() {
+        let text = series().render_terminal(20, 4);
+        let lines: Vec<&str> = text.lines().collect();
+        assert!(lines[0].starts_with("1150 ┤"));
+        assert!(lines[3].starts_with(" 950 ┤"));
+        assert_eq!(lines[0].chars().count(), 6 + 20);
+        assert!(lines[5].ends_with("round 7"));
+        assert!(lines[6].contains('╰'));
+        assert!(text.contains("High-water mark: 1150 chips (round 6)"));
+        assert!(text.contains("Max drawdown: -150 chips, from round 2 to round 4"));
+
+        assert!(BalanceSeries::new(vec![1000])
+            .render_terminal(20, 4)
+            .contains("at least one round"));
+    }
+
+    #[test]
+    fn test_render_svg() {
+        let svg = series().render_svg(640, 320);
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert_eq!(svg.matches("<polyline").count(), 2);
+        assert!(svg.contains("Max drawdown: -150 chips"));
+    }
+
+    #[test]
+    fn test_from_records() {
+        let record = |chip_delta: i64, chips: u32| RoundRecord {
+            chip_delta,
+            chips,
+            ..fixtures::record()
+        };
+        let series = BalanceSeries::from_records(&[record(10, 1010), record(-20, 990)]);
+        assert_eq!(series.balances, vec![1000, 1010, 990]);
+        assert!(BalanceSeries::from_records(&[]).balances.is_empty());
+    }
+}
@@ -0,0 +1,243 @@
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::stats::PlayerStats;
+
+pub const STARTING_CHIPS: u32 = 1000;
+
+// Where profiles, saves and history live: $BLACKJACK_HOME, or ~/.blackjack-cli
+pub fn data_dir() -> PathBuf {
+    if let Some(dir) = env::var_os("BLACKJACK_HOME") {
+        return PathBuf::from(dir);
+    }
+    env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .map(|home| PathBuf::from(home).join(".blackjack-cli"))
+        .unwrap_or_else(|| PathBuf::from(".blackjack-cli"))
+}
+
+// Writes to a temporary file first so a crash mid-write can't corrupt the old copy
+pub fn write_atomically(path: &Path, contents: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|err| format!("Couldn't create {}: {err}", parent.display()))?;
+    }
+    let temp = path.with_extension("tmp");
+    fs::write(&temp, contents)
+        .map_err(|err| format!("Couldn't write {}: {err}", temp.display()))?;
+    fs::rename(&temp, path).map_err(|err| format!("Couldn't save {}: {err}", path.display()))
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Preferences {
+    pub show_odds: bool,
+    pub review: bool,
+    pub count_checks: bool,
+    pub betting: Option<String>,
+    pub base_bet: Option<u32>,
+    pub theme: Option<String>,
+    pub suit_letters: bool,
+    pub card_art: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    pub bankroll: u32,
+    #[serde(default)]
+    pub stats: PlayerStats,
+    #[serde(default)]
+    pub preferences: Preferences,
+}
+
+impl Profile {
+    pub fn new(name: &str) -> Self {
+        Profile {
+            name: name.to_string(),
+            bankroll: STARTING_CHIPS,
+            stats: PlayerStats::default(),
+            preferences: Preferences::default(),
+        }
+    }
+
+    // A profile that went broke last time starts again with a fresh bankroll
+    pub fn rebuy_if_broke(&mut self) -> bool {
+        if self.bankroll > 0 {
+            return false;
+        }
+        self.bankroll = STARTING_CHIPS;
+        self.stats.rebuys += 1;
+        true
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "{}: {} chips, {} rounds played, {:.1}% won, net {:+} chips",
+            self.name,
+            self.bankroll,
+            self.stats.rounds,
+            self.stats.win_rate(),
+            self.stats.net
+        )
+    }
+}
+
+pub fn valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 32
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+pub struct ProfileStore {
+    dir: PathBuf,
+}
+
+impl ProfileStore {
+    pub fn new(dir: PathBuf) -> Self {
+        ProfileStore { dir }
+    }
+
+    pub fn default_location() -> Self {
+        ProfileStore::new(data_dir().join("profiles"))
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{name}.json"))
+    }
+
+    pub fn list(&self) -> Vec<String> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        let mut names: Vec<String> = entries
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                if path.extension()? != "json" {
+                    return None;
+                }
+                Some(path.file_stem()?.to_str()?.to_string())
+            })
+            .collect();
+        names.sort();
+        names
+    }
+
+    pub fn load(&self, name: &str) -> Result<Option<Profile>, String> {
+        if !valid_name(name) {
+            return Err(format!(
+                "Invalid profile name: {name} (use letters, digits, - and _)"
+            ));
+        }
+        let path = self.path(name);
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(format!("Couldn't read {}: {err}", path.display())),
+        };
+        serde_json::from_str(&text)
+            .map(Some)
+            .map_err(|err| format!("Couldn't parse {}: {err}", path.display()))
+    }
+
+    pub fn load_or_create(&self, name: &str) -> Result<Profile, String> {
+        Ok(self.load(name)?.unwrap_or_else(|| Profile::new(name)))
+    }
+
+    pub fn save(&self, profile: &Profile) -> Result<(), String> {
+        let text = serde_json::to_string_pretty(profile).map_err(|err| err.to_string())?;
+        write_atomically(&self.path(&profile.name), &text)
+    }
+}
+
+// Lists saved profiles and asks which to use; None means play as a guest
+pub fn choose_profile(store: &ProfileStore) -> Option<String> {
+    let names = store.list();
+    if names.is_empty() {
+        return None;
+    }
+
+    println!("Saved profiles: {}", names.join(", "));
+    loop {
+        print!("Enter a profile name, or press Enter to play as a guest: ");
+        io::stdout().flush().unwrap();
+
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input).unwrap_or(0) == 0 {
+            return None;
+        }
+        let name = input.trim();
+        if name.is_empty() {
+            return None;
+        }
+        if valid_name(name) {
+            return Some(name.to_string());
+        }
+        println!("Profile names can only use letters, digits, - and _.");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::outcome::Outcome;
+
+    fn temp_store(test: &str) -> ProfileStore {
+        let dir = env::temp_dir().join(format!("blackjack-cli-{test}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        ProfileStore::new(dir)
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let store = temp_store("profiles");
+        assert!(store.list().is_empty());
+        assert_eq!(store.load("alice"), Ok(None));
+
+        let mut profile = store.load_or_create("alice").expect("new profile");
+        assert_eq!(profile.bankroll, STARTING_CHIPS);
+        profile.bankroll = 1250;
+        profile
+            .stats
+            .record(Outcome::PlayerBlackjack, 100, 150, Some(10), &[]);
+        profile.preferences.show_odds = true;
+        store.save(&profile).expect("saved");
+        store.save(&Profile::new("bob")).expect("saved");
+
+        assert_eq!(store.list(), vec!["alice", "bob"]);
+        assert_eq!(store.load("alice"), Ok(Some(profile)));
+        let _ = fs::remove_dir_all(&store.dir);
+    }
+
+    #[test]
+    fn test_missing_fields_use_defaults() {
+        let profile: Profile =
+            serde_json::from_str(r#"{"name": "carol", "bankroll": 40}"#).expect("valid json");
+        assert_eq!(profile.stats, PlayerStats::default());
+        assert!(!profile.preferences.show_odds);
+    }
+
+    #[test]
+    fn test_invalid_names() {
+        let store = temp_store("names");
+        assert!(store.load("../etc/passwd").is_err());
+        assert!(!valid_name(""));
+        assert!(valid_name("card_counter-2"));
+    }
+
+    #[test]
+    fn test_rebuy_if_broke() {
+        let mut profile = Profile::new("dave");
+        assert!(!profile.rebuy_if_broke());
+        profile.bankroll = 0;
+        assert!(profile.rebuy_if_broke());
+        assert_eq!(profile.bankroll, STARTING_CHIPS);
+        assert_eq!(profile.stats.rebuys, 1);
+    }
+}
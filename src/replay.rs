@@ -0,0 +1,399 @@
+use std::io::{self, Write};
+
+use crate::display::{card_label, render_outcome};
+use crate::hand::Hand;
+use crate::history::{self, HistorySource, RoundRecord};
+use crate::outcome::Outcome;
+use crate::strategy::{Action, Strategy};
+use crate::whatif::{self, RoundBranches};
+
+fn prompt(message: &str) -> Option<String> {
+    print!("{message}");
+    io::stdout().flush().unwrap();
+    let mut input = String::new();
+    match io::stdin().read_line(&mut input) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(input.trim().to_string()),
+    }
+}
+
+// Lets the player take a different action at one of the round's decisions
+fn what_if(record: &RoundRecord) {
+    let branches = match RoundBranches::new(record) {
+        Ok(branches) => branches,
+        Err(err) => {
+            println!("{err}");
+            return;
+        }
+    };
+    if branches.points().is_empty() {
+        println!("You had no decisions to make in this round.");
+        return;
+    }
+
+    println!();
+    for (i, point) in branches.points().iter().enumerate() {
+        println!("{}. {}", i + 1, whatif::describe_point(point));
+    }
+    let Some(input) = prompt("Branch from which decision? ") else {
+        return;
+    };
+    let point = match input.parse::<usize>() {
+        Ok(n) if (1..=branches.points().len()).contains(&n) => n - 1,
+        _ => {
+            println!("Pick a decision from 1 to {}.", branches.points().len());
+            return;
+        }
+    };
+
+    let Some(input) = prompt("Hit (h) or Stand (s) instead? ") else {
+        return;
+    };
+    let action = match Action::parse(&input) {
+        Some(action @ (Action::Hit | Action::Stand)) => action,
+        _ => {
+            println!("Only hit and stand can be played here.");
+            return;
+        }
+    };
+    let branch = branches.play(point, action, &Strategy::new());
+    println!("\n{}", whatif::render_branch(&branch, record.chip_delta));
+}
+
+// A recorded round played back from its cards, with the result worked out again
+pub struct ReplayedRound {
+    pub record: RoundRecord,
+    pub player_hand: Hand,
+    pub dealer_hand: Hand,
+    pub outcome: Outcome,
+    pub chip_delta: i64,
+    pub chips_before: i64,
+    pub chips: i64,
+}
+
+impl ReplayedRound {
+    pub fn from_record(record: RoundRecord, chips_before: i64) -> ReplayedRound {
+        let mut player_hand = Hand::new();
+        for &card in &record.player_cards {
+            player_hand.add_card(card);
+        }
+        for card in record.actions.iter().filter_map(|action| action.card) {
+            player_hand.add_card(card);
+        }
+
+        let mut dealer_hand = Hand::new();
+        for &card in record.dealer_cards.iter().chain(&record.dealer_draws) {
+            dealer_hand.add_card(card);
+        }
+
+        let outcome = Outcome::of(&player_hand, &dealer_hand);
+        let chip_delta = outcome.chip_delta(record.bet, &record.rules);
+        ReplayedRound {
+            record,
+            player_hand,
+            dealer_hand,
+            outcome,
+            chip_delta,
+            chips_before,
+            chips: chips_before + chip_delta,
+        }
+    }
+
+    // Differences between what was recorded and what the cards say happened
+    pub fn mismatches(&self) -> Vec<String> {
+        let mut mismatches = Vec::new();
+        if self.outcome != self.record.outcome {
+            mismatches.push(format!(
+                "recorded {:?} but the cards give {:?}",
+                self.record.outcome, self.outcome
+            ));
+        }
+        if self.chip_delta != self.record.chip_delta {
+            mismatches.push(format!(
+                "recorded {:+} chips but the cards give {:+}",
+                self.record.chip_delta, self.chip_delta
+            ));
+        }
+        if self.chips != self.record.chips as i64 {
+            mismatches.push(format!(
+                "recorded a total of {} chips but the replay has {}",
+                self.record.chips, self.chips
+            ));
+        }
+        mismatches
+    }
+
+    pub fn render(&self, position: usize, rounds: usize) -> String {
+        let mut lines = vec![
+            format!(
+                "=== Round {} of {rounds} (bet {}, card {} of the shoe) ===",
+                position + 1,
+                self.record.bet,
+                self.record.shoe_position + 1
+            ),
+            String::new(),
+            "=== Dealer's Hand ===".to_string(),
+            self.dealer_hand.to_string(),
+            String::new(),
+            "=== Your Hand ===".to_string(),
+            self.player_hand.to_string(),
+            String::new(),
+        ];
+
+        let actions: Vec<String> = self
+            .record
+            .actions
+            .iter()
+            .map(|action| match action.card {
+                Some(card) => format!("{:?} ({})", action.action, card_label(&card)),
+                None => format!("{:?}", action.action),
+            })
+            .collect();
+        if !actions.is_empty() {
+            lines.push(format!("You: {}", actions.join(", ")));
+        }
+        if !self.record.dealer_draws.is_empty() {
+            let draws: Vec<String> = self
+                .record
+                .dealer_draws
+                .iter()
+                .map(|card| card.to_string())
+                .collect();
+            lines.push(format!("Dealer drew: {}", draws.join(", ")));
+        }
+        lines.push(render_outcome(self.outcome));
+        lines.push(format!(
+            "Chips: {} -> {} ({:+})",
+            self.chips_before, self.chips, self.chip_delta
+        ));
+        for mismatch in self.mismatches() {
+            lines.push(format!("Mismatch: {mismatch}"));
+        }
+        lines.join("\n")
+    }
+}
+
+// Chips carry over from round to round within a session. Each new session starts
+// from the total recorded before its first round.
+pub fn replay(records: Vec<RoundRecord>) -> Vec<ReplayedRound> {
+    let mut rounds: Vec<ReplayedRound> = Vec::new();
+    for record in records {
+        let chips_before = match rounds.last() {
+            Some(previous) if previous.record.session == record.session => previous.chips,
+            _ => record.chips as i64 - record.chip_delta,
+        };
+        rounds.push(ReplayedRound::from_record(record, chips_before));
+    }
+    rounds
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplayCommand {
+    Next,
+    Previous,
+    Jump(usize),
+    WhatIf,
+    Quit,
+}
+
+impl ReplayCommand {
+    pub fn parse(input: &str) -> Option<ReplayCommand> {
+        match input.trim().to_lowercase().as_str() {
+            "" | "n" | "next" => Some(ReplayCommand::Next),
+            "p" | "prev" | "previous" => Some(ReplayCommand::Previous),
+            "w" | "what-if" | "whatif" => Some(ReplayCommand::WhatIf),
+            "q" | "quit" => Some(ReplayCommand::Quit),
+            other => other
+                .trim_start_matches('j')
+                .trim()
+                .parse()
+                .ok()
+                .map(ReplayCommand::Jump),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ReplayOptions {
+    pub history: HistorySource,
+    pub check: bool,
+}
+
+impl ReplayOptions {
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let mut options = ReplayOptions::default();
+        let mut iter = args.iter();
+
+        while let Some(arg) = iter.next() {
+            if options.history.apply_option(arg, &mut iter)? {
+                continue;
+            }
+            match arg.as_str() {
+                "--check" => options.check = true,
+                _ => return Err(format!("Unknown option: {arg}")),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+// Checks every round, then steps through them unless only a check was asked for
+pub fn run(options: &ReplayOptions) -> Result<(), String> {
+    let path = options.history.path();
+    let rounds = replay(history::read(&path)?);
+    if rounds.is_empty() {
+        return Err(format!("No rounds recorded in {}", path.display()));
+    }
+
+    let bad: Vec<usize> = (0..rounds.len())
+        .filter(|&i| !rounds[i].mismatches().is_empty())
+        .collect();
+    if bad.is_empty() {
+        println!(
+            "All {} rounds reproduce the recorded chip totals.",
+            rounds.len()
+        );
+    } else {
+        let numbers: Vec<String> = bad.iter().map(|i| (i + 1).to_string()).collect();
+        println!(
+            "{} of {} rounds don't match the recording: {}",
+            bad.len(),
+            rounds.len(),
+            numbers.join(", ")
+        );
+    }
+
+    if options.check {
+        return match bad.len() {
+            0 => Ok(()),
+            _ => Err(format!("{} has rounds that don't replay", path.display())),
+        };
+    }
+
+    let mut position = 0;
+    let mut redraw = true;
+    loop {
+        if redraw {
+            println!("\n{}\n", rounds[position].render(position, rounds.len()));
+        }
+        let Some(input) =
+            prompt("Next (Enter), previous (p), round number to jump, what-if (w) or quit (q)? ")
+        else {
+            return Ok(());
+        };
+
+        // Only moving to another round redraws the table
+        redraw = match ReplayCommand::parse(&input) {
+            Some(ReplayCommand::Next) if position + 1 < rounds.len() => {
+                position += 1;
+                true
+            }
+            Some(ReplayCommand::Previous) if position > 0 => {
+                position -= 1;
+                true
+            }
+            Some(ReplayCommand::Jump(round)) if (1..=rounds.len()).contains(&round) => {
+                position = round - 1;
+                true
+            }
+            Some(ReplayCommand::Next) => {
+                println!("That was the last round.");
+                false
+            }
+            Some(ReplayCommand::Previous) => {
+                println!("This is the first round.");
+                false
+            }
+            Some(ReplayCommand::Jump(_)) => {
+                println!("Pick a round from 1 to {}.", rounds.len());
+                false
+            }
+            Some(ReplayCommand::WhatIf) => {
+                what_if(&rounds[position].record);
+                println!();
+                false
+            }
+            Some(ReplayCommand::Quit) => return Ok(()),
+            None => {
+                println!("Invalid input!");
+                false
+            }
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::fixtures::{self, cards};
+
+    fn record(session: u64, player: &[&str], dealer: &[&str], hits: &[&str]) -> RoundRecord {
+        RoundRecord {
+            session,
+            player_cards: cards(&player[..2]),
+            dealer_cards: cards(&dealer[..2]),
+            actions: fixtures::hits(hits),
+            dealer_draws: cards(&dealer[2..]),
+            ..fixtures::record()
+        }
+    }
+
+    // Fills in the result the way the game would have recorded it
+    fn played(mut record: RoundRecord, chips_before: i64) -> RoundRecord {
+        let round = ReplayedRound::from_record(record.clone(), chips_before);
+        record.outcome = round.outcome;
+        record.chip_delta = round.chip_delta;
+        record.chips = round.chips as u32;
+        record
+    }
+
+    #[test]
+    fn test_replay_reproduces_chip_totals() {
+        let records = vec![
+            // 14 + 5 = 19 against the dealer's 16 + K, a bust
+            played(record(1, &["TH", "4C"], &["9S", "7D", "KH"], &["5S"]), 1000),
+            played(record(1, &["AH", "KC"], &["9S", "7D"], &[]), 1010),
+            // A new session starts wherever its first round says
+            played(record(2, &["TH", "8C"], &["TS", "QD"], &[]), 500),
+        ];
+        let rounds = replay(records);
+
+        assert_eq!(rounds[0].outcome, Outcome::DealerBust);
+        assert_eq!(rounds[1].outcome, Outcome::PlayerBlackjack);
+        assert_eq!(rounds[1].chips, 1025);
+        assert_eq!(rounds[2].chips_before, 500);
+        assert!(rounds.iter().all(|round| round.mismatches().is_empty()));
+
+        let text = rounds[0].render(0, 3);
+        assert!(text.contains("Round 1 of 3"));
+        assert!(text.contains("Hit (5♠), Stand"));
+        assert!(text.contains("Chips: 1000 -> 1010 (+10)"));
+    }
+
+    #[test]
+    fn test_replay_catches_bad_records() {
+        let mut first = played(record(1, &["TH", "9C"], &["TS", "7D"], &[]), 1000);
+        first.chip_delta = 20;
+        first.chips = 1020;
+        let second = played(record(1, &["TH", "8C"], &["TS", "QD"], &[]), 1020);
+        let rounds = replay(vec![first, second]);
+
+        assert_eq!(rounds[0].mismatches().len(), 2);
+        assert!(rounds[0].render(0, 2).contains("Mismatch"));
+        // The second round carries on from the replayed total, not the recorded one
+        assert_eq!(rounds[1].chips_before, 1010);
+        assert_eq!(rounds[1].mismatches().len(), 1);
+    }
+
+    #[test]
+    fn test_commands() {
+        assert_eq!(ReplayCommand::parse("\n"), Some(ReplayCommand::Next));
+        assert_eq!(ReplayCommand::parse("P"), Some(ReplayCommand::Previous));
+        assert_eq!(ReplayCommand::parse("12"), Some(ReplayCommand::Jump(12)));
+        assert_eq!(ReplayCommand::parse("j 3"), Some(ReplayCommand::Jump(3)));
+        assert_eq!(ReplayCommand::parse("w"), Some(ReplayCommand::WhatIf));
+        assert_eq!(ReplayCommand::parse("q"), Some(ReplayCommand::Quit));
+        assert_eq!(ReplayCommand::parse("x"), None);
+    }
+}
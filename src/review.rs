@@ -0,0 +1,242 @@
+use std::fmt;
+
+use crate::analysis::ShoeComposition;
+use crate::card::Card;
+use crate::display::card_label;
+use crate::ev;
+use crate::hand::Hand;
+use crate::rules::Rules;
+use crate::strategy::{Action, Available, Recommendation};
+
+// Costs below this are rounding noise, not mistakes
+const MISTAKE_THRESHOLD: f64 = 1e-9;
+
+#[derive(Debug, Clone)]
+pub struct Decision {
+    pub cards: Vec<Card>,
+    pub upcard: Card,
+    pub chosen: Action,
+    pub best: Action,
+    // Expected value given up, in units of the bet
+    pub cost: f64,
+    // What the strategy tables say at the current count
+    pub strategy: Option<Recommendation>,
+}
+
+impl Decision {
+    // The shoe holds every card the player hasn't seen, including the dealer's hole card
+    pub fn evaluate(
+        hand: &Hand,
+        upcard: &Card,
+        unseen: &ShoeComposition,
+        rules: &Rules,
+        available: Available,
+        chosen: Action,
+    ) -> Decision {
+        let values = ev::action_values(hand, upcard, unseen, rules, available);
+        Decision {
+            cards: hand.cards().to_vec(),
+            upcard: *upcard,
+            chosen,
+            best: values.best().0,
+            cost: values.cost(chosen).unwrap_or(0.0),
+            strategy: None,
+        }
+    }
+
+    pub fn with_strategy(mut self, recommendation: Recommendation) -> Self {
+        self.strategy = Some(recommendation);
+        self
+    }
+
+    pub fn is_mistake(&self) -> bool {
+        self.cost > MISTAKE_THRESHOLD
+    }
+}
+
+impl fmt::Display for Decision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cards: Vec<String> = self.cards.iter().map(card_label).collect();
+        write!(
+            f,
+            "{} vs {}: {}",
+            cards.join(" "),
+            card_label(&self.upcard),
+            self.chosen
+        )?;
+        if self.is_mistake() {
+            write!(
+                f,
+                " (best: {}, cost {:.1}% of the bet)",
+                self.best,
+                self.cost * 100.0
+            )?;
+        } else {
+            write!(f, " (best play)")?;
+        }
+        if let Some(Recommendation {
+            basic,
+            deviation: Some(play),
+            ..
+        }) = &self.strategy
+        {
+            write!(
+                f,
+                "\n  Index play {play} overrides basic strategy ({basic}) at this count"
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Review {
+    pub round: Vec<Decision>,
+    pub decisions: u32,
+    pub mistakes: u32,
+    // Summed over the session, in chips since bet sizes vary between rounds
+    pub total_cost: f64,
+}
+
+impl Review {
+    pub fn new() -> Self {
+        Review::default()
+    }
+
+    pub fn start_round(&mut self) {
+        self.round.clear();
+    }
+
+    pub fn record(&mut self, decision: Decision, bet: u32) {
+        self.decisions += 1;
+        if decision.is_mistake() {
+            self.mistakes += 1;
+            self.total_cost += decision.cost * bet as f64;
+        }
+        self.round.push(decision);
+    }
+
+    // Only counts mistakes, matching what record adds to the session total
+    pub fn round_cost(&self) -> f64 {
+        self.round
+            .iter()
+            .filter(|decision| decision.is_mistake())
+            .map(|decision| decision.cost)
+            .sum()
+    }
+
+    pub fn print_round(&self, bet: u32) {
+        if self.round.is_empty() {
+            return;
+        }
+        println!("=== Review ===");
+        for decision in &self.round {
+            println!("{decision}");
+        }
+        println!(
+            "Cost of mistakes this hand: {:.2} chips",
+            self.round_cost() * bet as f64
+        );
+        println!();
+    }
+
+    pub fn print_summary(&self) {
+        println!("=== Decision review ===");
+        println!("Mistakes: {}/{} decisions", self.mistakes, self.decisions);
+        println!("Total cost of mistakes: {:.2} chips", self.total_cost);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::{Rank, Suit};
+    use crate::strategy::Strategy;
+
+    fn card(rank: Rank) -> Card {
+        Card {
+            suit: Suit::Spades,
+            rank,
+        }
+    }
+
+    fn decide(ranks: &[Rank], upcard: Rank, chosen: Action) -> Decision {
+        let mut hand = Hand::new();
+        for &rank in ranks {
+            hand.add_card(card(rank));
+        }
+        let upcard = card(upcard);
+        let rules = Rules::single_deck();
+        let unseen = ShoeComposition::full(rules.decks)
+            .without(hand.cards())
+            .without(&[upcard]);
+        Decision::evaluate(
+            &hand,
+            &upcard,
+            &unseen,
+            &rules,
+            Available::HIT_STAND,
+            chosen,
+        )
+    }
+
+    #[test]
+    fn test_hitting_twenty_is_a_mistake() {
+        let decision = decide(&[Rank::King, Rank::Queen], Rank::Six, Action::Hit);
+        assert_eq!(decision.best, Action::Stand);
+        assert!(decision.is_mistake());
+        assert!(decision.cost > 0.5);
+        assert!(decision.to_string().contains("best: Stand"));
+
+        let decision = decide(&[Rank::King, Rank::Queen], Rank::Six, Action::Stand);
+        assert!(!decision.is_mistake());
+        assert_eq!(decision.cost, 0.0);
+    }
+
+    #[test]
+    fn test_index_play_is_mentioned() {
+        let mut hand = Hand::new();
+        hand.add_card(card(Rank::Ten));
+        hand.add_card(card(Rank::Six));
+        let upcard = card(Rank::King);
+        let strategy = Strategy::new();
+
+        let decision = decide(&[Rank::Ten, Rank::Six], Rank::King, Action::Stand);
+        let at_count = strategy.recommend(&hand, &upcard, Some(2.0), Available::HIT_STAND);
+        let text = decision.with_strategy(at_count).to_string();
+        assert!(text.contains("Index play"));
+        assert!(text.contains("overrides basic strategy (Hit)"));
+
+        let decision = decide(&[Rank::Ten, Rank::Six], Rank::King, Action::Hit);
+        let at_count = strategy.recommend(&hand, &upcard, Some(-2.0), Available::HIT_STAND);
+        assert!(!decision
+            .with_strategy(at_count)
+            .to_string()
+            .contains("Index play"));
+    }
+
+    #[test]
+    fn test_review_totals() {
+        let mut review = Review::new();
+        let mistake = decide(&[Rank::King, Rank::Queen], Rank::Six, Action::Hit);
+        let cost = mistake.cost;
+
+        review.record(mistake, 10);
+        review.record(decide(&[Rank::Five, Rank::Six], Rank::Six, Action::Hit), 10);
+        assert_eq!(review.round.len(), 2);
+        assert_eq!(review.decisions, 2);
+        assert_eq!(review.mistakes, 1);
+        assert!((review.total_cost - cost * 10.0).abs() < 1e-9);
+        assert_eq!(review.round_cost(), cost);
+
+        // Rounding noise isn't charged to the hand
+        let mut noise = decide(&[Rank::Five, Rank::Six], Rank::Six, Action::Hit);
+        noise.cost = MISTAKE_THRESHOLD / 2.0;
+        review.record(noise, 10);
+        assert_eq!(review.round_cost(), cost);
+
+        review.start_round();
+        assert!(review.round.is_empty());
+        assert_eq!(review.decisions, 3);
+    }
+}
@@ -0,0 +1,310 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::hand::Hand;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Rules {
+    pub decks: usize,
+    pub dealer_hits_soft_17: bool,
+    pub double_after_split: bool,
+    pub surrender: bool,
+    pub blackjack_payout: (u32, u32),
+    pub resplit_aces: bool,
+    pub max_split_hands: usize,
+    pub dealer_peeks: bool,
+    pub penetration: f64,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            decks: 6,
+            dealer_hits_soft_17: false,
+            double_after_split: true,
+            surrender: true,
+            blackjack_payout: (3, 2),
+            resplit_aces: false,
+            max_split_hands: 4,
+            dealer_peeks: true,
+            penetration: 0.75,
+        }
+    }
+}
+
+impl Rules {
+    // The interactive game deals from a single deck, has no doubling, splitting or
+    // surrender, and only reveals a dealer blackjack after the player has acted
+    pub fn single_deck() -> Self {
+        Rules {
+            decks: 1,
+            double_after_split: false,
+            surrender: false,
+            max_split_hands: 1,
+            dealer_peeks: false,
+            ..Rules::default()
+        }
+    }
+
+    // Named rule sets, matched loosely so "Vegas Strip" and vegas-strip both work
+    pub fn preset(name: &str) -> Option<Rules> {
+        let name = name.to_lowercase().replace([' ', '_'], "-");
+        let rules = match name.as_str() {
+            "vegas-strip" | "strip" => Rules {
+                decks: 4,
+                surrender: false,
+                ..Rules::default()
+            },
+            "atlantic-city" | "ac" => Rules {
+                decks: 8,
+                ..Rules::default()
+            },
+            "downtown-vegas" | "downtown" => Rules {
+                decks: 2,
+                dealer_hits_soft_17: true,
+                surrender: false,
+                ..Rules::default()
+            },
+            "european" | "europe" => Rules {
+                surrender: false,
+                max_split_hands: 2,
+                dealer_peeks: false,
+                ..Rules::default()
+            },
+            _ => return None,
+        };
+        Some(rules)
+    }
+
+    pub fn dealer_hits(&self, hand: &Hand) -> bool {
+        self.dealer_hits_total(hand.value(), hand.is_soft())
+    }
+
+    pub fn dealer_hits_total(&self, value: u8, soft: bool) -> bool {
+        value < 17 || (value == 17 && soft && self.dealer_hits_soft_17)
+    }
+
+    pub fn blackjack_winnings(&self, bet: u32) -> u32 {
+        let (numerator, denominator) = self.blackjack_payout;
+        bet * numerator / denominator
+    }
+
+    pub fn blackjack_ratio(&self) -> f64 {
+        let (numerator, denominator) = self.blackjack_payout;
+        numerator as f64 / denominator as f64
+    }
+
+    pub fn cut_card(&self) -> usize {
+        let total = (self.decks * 52) as f64;
+        (total * (1.0 - self.penetration)).round() as usize
+    }
+
+    // Returns Ok(false) when the argument isn't a rule option
+    pub fn apply_option<'a>(
+        &mut self,
+        arg: &str,
+        rest: &mut impl Iterator<Item = &'a String>,
+    ) -> Result<bool, String> {
+        let mut value = |name: &str| {
+            rest.next()
+                .map(String::as_str)
+                .ok_or_else(|| format!("Missing value for {name}"))
+        };
+
+        match arg {
+            "--preset" => {
+                let name = value(arg)?;
+                *self = Rules::preset(name).ok_or_else(|| {
+                    format!(
+                        "Unknown preset: {name} (expected vegas-strip, atlantic-city, downtown-vegas or european)"
+                    )
+                })?;
+            }
+            "--decks" => {
+                self.decks = match value(arg)?.parse::<usize>() {
+                    Ok(decks @ 1..=8) => decks,
+                    _ => return Err("Decks must be between 1 and 8".to_string()),
+                }
+            }
+            "--h17" => self.dealer_hits_soft_17 = true,
+            "--s17" => self.dealer_hits_soft_17 = false,
+            "--das" => self.double_after_split = true,
+            "--no-das" => self.double_after_split = false,
+            "--surrender" => self.surrender = true,
+            "--no-surrender" => self.surrender = false,
+            "--resplit-aces" => self.resplit_aces = true,
+            "--no-hole-card" => self.dealer_peeks = false,
+            "--payout" => {
+                let text = value(arg)?;
+                self.blackjack_payout = text
+                    .split_once(':')
+                    .and_then(|(n, d)| Some((n.parse().ok()?, d.parse().ok()?)))
+                    .filter(|&(_, d): &(u32, u32)| d > 0)
+                    .ok_or_else(|| format!("Invalid payout: {text} (expected e.g. 3:2)"))?;
+            }
+            "--max-splits" => {
+                self.max_split_hands = match value(arg)?.parse::<usize>() {
+                    Ok(hands @ 1..=4) => hands,
+                    _ => return Err("Split hands must be between 1 and 4".to_string()),
+                }
+            }
+            "--penetration" => {
+                self.penetration = match value(arg)?.parse::<f64>() {
+                    Ok(p) if (0.1..=0.95).contains(&p) => p,
+                    _ => return Err("Penetration must be between 0.1 and 0.95".to_string()),
+                }
+            }
+            _ => return Ok(false),
+        }
+
+        Ok(true)
+    }
+}
+
+impl fmt::Display for Rules {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (numerator, denominator) = self.blackjack_payout;
+        write!(
+            f,
+            "{} deck{}, {}, {}, {}, blackjack pays {numerator}:{denominator}",
+            self.decks,
+            if self.decks == 1 { "" } else { "s" },
+            if self.dealer_hits_soft_17 {
+                "H17"
+            } else {
+                "S17"
+            },
+            if self.double_after_split {
+                "DAS"
+            } else {
+                "no DAS"
+            },
+            if self.surrender {
+                "late surrender"
+            } else {
+                "no surrender"
+            },
+        )?;
+        if self.resplit_aces {
+            write!(f, ", resplit aces")?;
+        }
+        if !self.dealer_peeks {
+            write!(f, ", no hole card")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::{Card, Rank, Suit};
+
+    fn hand(ranks: &[Rank]) -> Hand {
+        let mut hand = Hand::new();
+        for &rank in ranks {
+            hand.add_card(Card {
+                suit: Suit::Clubs,
+                rank,
+            });
+        }
+        hand
+    }
+
+    #[test]
+    fn test_dealer_soft_17() {
+        let soft_17 = hand(&[Rank::Ace, Rank::Six]);
+        let hard_17 = hand(&[Rank::Ten, Rank::Seven]);
+
+        let s17 = Rules::default();
+        assert!(!s17.dealer_hits(&soft_17));
+        assert!(!s17.dealer_hits(&hard_17));
+
+        let h17 = Rules {
+            dealer_hits_soft_17: true,
+            ..Rules::default()
+        };
+        assert!(h17.dealer_hits(&soft_17));
+        assert!(!h17.dealer_hits(&hard_17));
+        assert!(h17.dealer_hits(&hand(&[Rank::Ten, Rank::Six])));
+    }
+
+    #[test]
+    fn test_blackjack_winnings() {
+        let rules = Rules::default();
+        assert_eq!(rules.blackjack_winnings(100), 150);
+        assert_eq!(rules.blackjack_winnings(5), 7);
+
+        let six_five = Rules {
+            blackjack_payout: (6, 5),
+            ..Rules::default()
+        };
+        assert_eq!(six_five.blackjack_winnings(100), 120);
+        assert_eq!(six_five.blackjack_ratio(), 1.2);
+    }
+
+    #[test]
+    fn test_apply_option() {
+        let args: Vec<String> = ["--decks", "2", "--h17", "--payout", "6:5", "--hands"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let mut rules = Rules::default();
+        let mut iter = args.iter();
+
+        while let Some(arg) = iter.next() {
+            if !rules.apply_option(arg, &mut iter).expect("valid option") {
+                assert_eq!(arg, "--hands");
+            }
+        }
+
+        assert_eq!(rules.decks, 2);
+        assert!(rules.dealer_hits_soft_17);
+        assert_eq!(rules.blackjack_payout, (6, 5));
+    }
+
+    #[test]
+    fn test_apply_option_errors() {
+        let bad = |list: &[&str]| {
+            let args: Vec<String> = list.iter().map(|s| s.to_string()).collect();
+            let mut iter = args.iter();
+            let arg = iter.next().unwrap();
+            Rules::default().apply_option(arg, &mut iter).is_err()
+        };
+
+        assert!(bad(&["--decks", "0"]));
+        assert!(bad(&["--decks"]));
+        assert!(bad(&["--payout", "3-2"]));
+        assert!(bad(&["--payout", "3:0"]));
+        assert!(bad(&["--penetration", "1.5"]));
+    }
+
+    #[test]
+    fn test_presets() {
+        let strip = Rules::preset("Vegas Strip").expect("known preset");
+        assert_eq!(strip.decks, 4);
+        assert!(!strip.surrender);
+        assert_eq!(Rules::preset("vegas-strip"), Some(strip));
+
+        assert_eq!(Rules::preset("atlantic_city").map(|r| r.decks), Some(8));
+        assert!(Rules::preset("downtown").unwrap().dealer_hits_soft_17);
+        assert!(!Rules::preset("european").unwrap().dealer_peeks);
+        assert!(Rules::preset("monte carlo").is_none());
+
+        // Later options override the preset
+        let args: Vec<String> = ["--decks", "6"].iter().map(|s| s.to_string()).collect();
+        let mut rules = strip;
+        let mut iter = args.iter();
+        let arg = iter.next().unwrap();
+        rules.apply_option(arg, &mut iter).expect("valid option");
+        assert_eq!(rules.decks, 6);
+        assert!(!rules.surrender);
+    }
+
+    #[test]
+    fn test_cut_card() {
+        let rules = Rules::default();
+        assert_eq!(rules.cut_card(), 78);
+    }
+}
@@ -0,0 +1,143 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::count::RunningCount;
+use crate::deck::Deck;
+use crate::hand::Hand;
+use crate::profile::{self, write_atomically};
+use crate::rules::Rules;
+
+// Bump when the format changes in a way older files can't be read as
+pub const SAVE_VERSION: u32 = 1;
+
+// Everything needed to carry on with the same shoe: the undealt cards in order,
+// the last hands dealt, the bankroll and the running count
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SavedGame {
+    pub version: u32,
+    pub deck: Deck,
+    #[serde(default)]
+    pub shoe_seed: Option<u64>,
+    pub player_hand: Hand,
+    pub dealer_hand: Hand,
+    pub player_chips: u32,
+    pub current_bet: u32,
+    pub running_count: RunningCount,
+    pub rules: Rules,
+}
+
+#[derive(Deserialize)]
+struct Version {
+    version: u32,
+}
+
+impl SavedGame {
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|err| err.to_string())
+    }
+
+    pub fn from_json(text: &str) -> Result<SavedGame, String> {
+        // Check the version first so an old or newer file gets a clear message
+        let Version { version } =
+            serde_json::from_str(text).map_err(|err| format!("Not a saved game: {err}"))?;
+        if version != SAVE_VERSION {
+            return Err(format!(
+                "Saved game version {version} isn't supported (expected {SAVE_VERSION})"
+            ));
+        }
+        serde_json::from_str(text).map_err(|err| format!("Corrupt saved game: {err}"))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        write_atomically(path, &self.to_json()?)
+    }
+
+    pub fn load(path: &Path) -> Result<Option<SavedGame>, String> {
+        match fs::read_to_string(path) {
+            Ok(text) => SavedGame::from_json(&text)
+                .map(Some)
+                .map_err(|err| format!("{}: {err}", path.display())),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(format!("Couldn't read {}: {err}", path.display())),
+        }
+    }
+}
+
+// Each profile has its own saved game; playing without one uses "guest"
+pub fn default_path(profile: Option<&str>) -> PathBuf {
+    profile::data_dir()
+        .join("saves")
+        .join(format!("{}.json", profile.unwrap_or("guest")))
+}
+
+pub fn remove(path: &Path) -> Result<(), String> {
+    match fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(format!("Couldn't remove {}: {err}", path.display())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn saved_game() -> SavedGame {
+        let mut deck = Deck::with_decks(2);
+        deck.shuffle();
+        let mut player_hand = Hand::new();
+        let mut running_count = RunningCount::new();
+        for _ in 0..2 {
+            let card = deck.deal().unwrap();
+            running_count.observe(&card);
+            player_hand.add_card(card);
+        }
+        SavedGame {
+            version: SAVE_VERSION,
+            deck,
+            shoe_seed: Some(42),
+            player_hand,
+            dealer_hand: Hand::new(),
+            player_chips: 740,
+            current_bet: 25,
+            running_count,
+            rules: Rules::single_deck(),
+        }
+    }
+
+    fn codes(deck: &Deck) -> Vec<String> {
+        deck.cards().iter().map(|card| card.code()).collect()
+    }
+
+    #[test]
+    fn test_round_trip_keeps_shoe_order() {
+        let saved = saved_game();
+        let path = env::temp_dir().join(format!("blackjack-cli-save-{}.json", std::process::id()));
+        saved.save(&path).expect("saved");
+
+        let loaded = SavedGame::load(&path).expect("readable").expect("exists");
+        assert_eq!(codes(&loaded.deck), codes(&saved.deck));
+        assert_eq!(loaded.deck.cards_remaining(), 102);
+        assert_eq!(loaded.player_hand.value(), saved.player_hand.value());
+        assert_eq!(loaded.player_chips, 740);
+        assert_eq!(loaded.running_count, saved.running_count);
+        assert_eq!(loaded.rules, Rules::single_deck());
+
+        remove(&path).expect("removed");
+        assert!(SavedGame::load(&path).expect("readable").is_none());
+    }
+
+    #[test]
+    fn test_rejects_other_versions() {
+        let mut saved = saved_game();
+        saved.version = SAVE_VERSION + 1;
+        let json = saved.to_json().unwrap();
+        let err = SavedGame::from_json(&json).err().unwrap();
+        assert!(err.contains("isn't supported"), "{err}");
+        assert!(SavedGame::from_json("{}").is_err());
+    }
+}
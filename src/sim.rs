@@ -0,0 +1,629 @@
+use std::fmt;
+
+use crate::bankroll::BetRamp;
+use crate::betting::BettingSystem;
+use crate::card::{Card, Rank};
+use crate::count::RunningCount;
+use crate::deck::Deck;
+use crate::hand::Hand;
+use crate::outcome::Outcome;
+use crate::rules::Rules;
+use crate::strategy::{Action, Available, Strategy};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayerStrategy {
+    Basic,
+    Counting,
+    MimicDealer,
+}
+
+impl PlayerStrategy {
+    pub fn parse(input: &str) -> Option<PlayerStrategy> {
+        match input.to_lowercase().as_str() {
+            "basic" => Some(PlayerStrategy::Basic),
+            "counting" => Some(PlayerStrategy::Counting),
+            "mimic" => Some(PlayerStrategy::MimicDealer),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for PlayerStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            PlayerStrategy::Basic => "basic strategy",
+            PlayerStrategy::Counting => "basic strategy with Hi-Lo index plays",
+            PlayerStrategy::MimicDealer => "mimic the dealer",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SimStats {
+    pub hands: u64,
+    pub wins: u64,
+    pub losses: u64,
+    pub pushes: u64,
+    pub total: f64,
+    pub total_squared: f64,
+    pub wagered: f64,
+}
+
+impl SimStats {
+    pub fn new() -> Self {
+        SimStats::default()
+    }
+
+    pub fn record(&mut self, result: f64) {
+        self.record_bet(result, 1.0);
+    }
+
+    // For rounds where the bet varies, the result is in units of the base bet
+    pub fn record_bet(&mut self, result: f64, bet: f64) {
+        let result = result * bet;
+        self.hands += 1;
+        self.wagered += bet;
+        self.total += result;
+        self.total_squared += result * result;
+
+        if result > 0.0 {
+            self.wins += 1;
+        } else if result < 0.0 {
+            self.losses += 1;
+        } else {
+            self.pushes += 1;
+        }
+    }
+
+    pub fn merge(&mut self, other: &SimStats) {
+        self.hands += other.hands;
+        self.wins += other.wins;
+        self.losses += other.losses;
+        self.pushes += other.pushes;
+        self.total += other.total;
+        self.total_squared += other.total_squared;
+        self.wagered += other.wagered;
+    }
+
+    pub fn expected_value(&self) -> f64 {
+        if self.hands == 0 {
+            return 0.0;
+        }
+        self.total / self.hands as f64
+    }
+
+    // Result per unit bet, which is what the edge means once bets vary
+    pub fn return_on_wagered(&self) -> f64 {
+        if self.wagered == 0.0 {
+            return 0.0;
+        }
+        self.total / self.wagered
+    }
+
+    pub fn average_bet(&self) -> f64 {
+        if self.hands == 0 {
+            return 0.0;
+        }
+        self.wagered / self.hands as f64
+    }
+
+    pub fn standard_deviation(&self) -> f64 {
+        if self.hands < 2 {
+            return 0.0;
+        }
+        let n = self.hands as f64;
+        let mean = self.total / n;
+        let variance = (self.total_squared - n * mean * mean) / (n - 1.0);
+        variance.max(0.0).sqrt()
+    }
+
+    // z is the number of standard errors, e.g. 1.96 for 95% confidence
+    pub fn confidence_interval(&self, z: f64) -> (f64, f64) {
+        let mean = self.expected_value();
+        if self.hands == 0 {
+            return (mean, mean);
+        }
+        let margin = z * self.standard_deviation() / (self.hands as f64).sqrt();
+        (mean - margin, mean + margin)
+    }
+
+    pub fn rate(&self, count: u64) -> f64 {
+        if self.hands == 0 {
+            return 0.0;
+        }
+        count as f64 / self.hands as f64
+    }
+}
+
+enum PlayedHand {
+    Finished(Hand, f64),
+    Split(Card, Card),
+    Surrendered,
+}
+
+pub struct Table {
+    rules: Rules,
+    player: PlayerStrategy,
+    strategy: Strategy,
+    shoe: Deck,
+    count: RunningCount,
+    // Seed for the next shoe, so a seeded run deals the same cards every time
+    seed: Option<u64>,
+}
+
+impl Table {
+    pub fn new(rules: Rules, player: PlayerStrategy) -> Self {
+        let mut table = Table {
+            rules,
+            player,
+            strategy: Strategy::new(),
+            shoe: Deck::with_decks(rules.decks),
+            count: RunningCount::new(),
+            seed: None,
+        };
+        table.shuffle();
+        table
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self.shuffle();
+        self
+    }
+
+    fn shuffle(&mut self) {
+        self.shoe = Deck::with_decks(self.rules.decks);
+        match self.seed {
+            Some(seed) => {
+                self.shoe.shuffle_seeded(seed);
+                self.seed = Some(seed.wrapping_add(1));
+            }
+            None => self.shoe.shuffle(),
+        }
+        self.count.reset();
+    }
+
+    pub fn true_count(&self) -> f64 {
+        self.count.true_count(self.shoe.cards_remaining())
+    }
+
+    fn draw_hidden(&mut self) -> Card {
+        if let Some(card) = self.shoe.deal() {
+            return card;
+        }
+        // Ran out mid-round, so finish it from a fresh shoe
+        self.shuffle();
+        self.shoe.deal().expect("a fresh shoe has cards")
+    }
+
+    fn draw(&mut self) -> Card {
+        let card = self.draw_hidden();
+        self.count.observe(&card);
+        card
+    }
+
+    fn decide(&self, hand: &Hand, upcard: &Card, available: Available) -> Action {
+        match self.player {
+            PlayerStrategy::Basic => {
+                self.strategy
+                    .recommend(hand, upcard, None, available)
+                    .action
+            }
+            PlayerStrategy::Counting => {
+                self.strategy
+                    .recommend(hand, upcard, Some(self.true_count()), available)
+                    .action
+            }
+            PlayerStrategy::MimicDealer => {
+                if self.rules.dealer_hits(hand) {
+                    Action::Hit
+                } else {
+                    Action::Stand
+                }
+            }
+        }
+    }
+
+    fn takes_insurance(&self) -> bool {
+        self.player == PlayerStrategy::Counting
+            && self.strategy.take_insurance(Some(self.true_count()))
+    }
+
+    fn play_hand(&mut self, mut hand: Hand, upcard: &Card, hands: usize) -> PlayedHand {
+        if hand.cards().len() == 1 {
+            let card = self.draw();
+            hand.add_card(card);
+        }
+
+        let can_split = hand.is_pair() && hands < self.rules.max_split_hands;
+
+        // Split aces get one card each unless they can be split again
+        if hand.is_from_split() && hand.cards()[0].rank == Rank::Ace {
+            if can_split && self.rules.resplit_aces {
+                return PlayedHand::Split(hand.cards()[0], hand.cards()[1]);
+            }
+            return PlayedHand::Finished(hand, 1.0);
+        }
+
+        loop {
+            if hand.value() >= 21 {
+                return PlayedHand::Finished(hand, 1.0);
+            }
+
+            let first_decision = hand.cards().len() == 2;
+            let available = Available {
+                double: first_decision && (!hand.is_from_split() || self.rules.double_after_split),
+                split: first_decision && can_split,
+                surrender: first_decision && !hand.is_from_split() && self.rules.surrender,
+            };
+
+            match self.decide(&hand, upcard, available) {
+                Action::Hit => {
+                    let card = self.draw();
+                    hand.add_card(card);
+                }
+                Action::Stand => return PlayedHand::Finished(hand, 1.0),
+                Action::Double => {
+                    let card = self.draw();
+                    hand.add_card(card);
+                    return PlayedHand::Finished(hand, 2.0);
+                }
+                Action::Split => return PlayedHand::Split(hand.cards()[0], hand.cards()[1]),
+                Action::Surrender => return PlayedHand::Surrendered,
+            }
+        }
+    }
+
+    // Plays one round with a one-unit bet and returns the net result in units
+    pub fn play_round(&mut self) -> f64 {
+        if self.shoe.cards_remaining() <= self.rules.cut_card() {
+            self.shuffle();
+        }
+
+        let mut player = Hand::new();
+        let mut dealer = Hand::new();
+        let card = self.draw();
+        player.add_card(card);
+        let hole_card = self.draw_hidden();
+        dealer.add_card(hole_card);
+        let card = self.draw();
+        player.add_card(card);
+        let upcard = self.draw();
+        dealer.add_card(upcard);
+
+        let mut result = 0.0;
+        if upcard.rank == Rank::Ace && self.takes_insurance() {
+            result += if dealer.is_blackjack() { 1.0 } else { -0.5 };
+        }
+
+        let dealer_checks = self.rules.dealer_peeks && dealer.is_blackjack();
+        if dealer_checks || player.is_blackjack() {
+            self.count.observe(&hole_card);
+            return result + Outcome::of(&player, &dealer).units(&self.rules);
+        }
+
+        let mut pending = vec![player];
+        let mut finished = Vec::new();
+        let mut hands = 1;
+
+        while let Some(hand) = pending.pop() {
+            match self.play_hand(hand, &upcard, hands) {
+                PlayedHand::Finished(hand, bet) => finished.push((hand, bet)),
+                // Without a peek a dealer blackjack still takes the whole bet
+                PlayedHand::Surrendered if dealer.is_blackjack() => result -= 1.0,
+                PlayedHand::Surrendered => result -= 0.5,
+                PlayedHand::Split(first, second) => {
+                    hands += 1;
+                    pending.push(Hand::from_split(second));
+                    pending.push(Hand::from_split(first));
+                }
+            }
+        }
+
+        self.count.observe(&hole_card);
+        if finished.iter().any(|(hand, _)| !hand.is_busted()) {
+            while self.rules.dealer_hits(&dealer) {
+                let card = self.draw();
+                dealer.add_card(card);
+            }
+        }
+
+        for (hand, bet) in &finished {
+            result += Outcome::of(hand, &dealer).units(&self.rules) * bet;
+        }
+        result
+    }
+}
+
+pub struct SimOptions {
+    pub hands: u64,
+    pub player: PlayerStrategy,
+    pub rules: Rules,
+    pub fast: bool,
+    pub threads: Option<usize>,
+    pub seed: Option<u64>,
+    pub ramp: Option<BetRamp>,
+    pub bankroll: Option<f64>,
+    pub session_hands: Option<u64>,
+    pub betting: Option<BettingSystem>,
+    pub sessions: u64,
+    pub table_max: u32,
+}
+
+impl Default for SimOptions {
+    fn default() -> Self {
+        SimOptions {
+            hands: 1_000_000,
+            player: PlayerStrategy::Basic,
+            rules: Rules::default(),
+            fast: false,
+            threads: None,
+            seed: None,
+            ramp: None,
+            bankroll: None,
+            session_hands: None,
+            betting: None,
+            sessions: 10_000,
+            table_max: 100,
+        }
+    }
+}
+
+impl SimOptions {
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let mut options = SimOptions::default();
+        let mut strategy_given = false;
+        let mut iter = args.iter();
+
+        while let Some(arg) = iter.next() {
+            if options.rules.apply_option(arg, &mut iter)? {
+                continue;
+            }
+            if arg == "--fast" {
+                options.fast = true;
+                continue;
+            }
+
+            let value = iter
+                .next()
+                .ok_or_else(|| format!("Missing value for {arg}"))?;
+            match arg.as_str() {
+                "--hands" => {
+                    options.hands = match value.replace(['_', ','], "").parse::<u64>() {
+                        Ok(hands) if hands > 0 => hands,
+                        _ => return Err(format!("Invalid number of hands: {value}")),
+                    }
+                }
+                "--strategy" => {
+                    options.player = PlayerStrategy::parse(value)
+                        .ok_or_else(|| format!("Unknown strategy: {value}"))?;
+                    strategy_given = true;
+                }
+                "--ramp" => options.ramp = Some(BetRamp::parse(value)?),
+                "--bankroll" => {
+                    options.bankroll = match value.replace(['_', ','], "").parse::<f64>() {
+                        Ok(units) if units > 0.0 => Some(units),
+                        _ => return Err(format!("Invalid bankroll: {value}")),
+                    }
+                }
+                "--session-hands" => {
+                    options.session_hands = match value.replace(['_', ','], "").parse::<u64>() {
+                        Ok(hands) if hands > 0 => Some(hands),
+                        _ => return Err(format!("Invalid number of hands: {value}")),
+                    }
+                }
+                "--threads" => {
+                    options.threads = match value.parse::<usize>() {
+                        Ok(threads) if threads > 0 => Some(threads),
+                        _ => return Err(format!("Invalid number of threads: {value}")),
+                    }
+                }
+                "--seed" => {
+                    options.seed = Some(
+                        value
+                            .parse::<u64>()
+                            .map_err(|_| format!("Invalid seed: {value}"))?,
+                    )
+                }
+                "--betting" => {
+                    options.betting = Some(
+                        BettingSystem::parse(value)
+                            .ok_or_else(|| format!("Unknown betting system: {value}"))?,
+                    )
+                }
+                "--sessions" => {
+                    options.sessions = match value.replace(['_', ','], "").parse::<u64>() {
+                        Ok(sessions) if sessions > 0 => sessions,
+                        _ => return Err(format!("Invalid number of sessions: {value}")),
+                    }
+                }
+                "--table-max" => {
+                    options.table_max = match value.parse::<u32>() {
+                        Ok(units) if units > 0 => units,
+                        _ => return Err(format!("Invalid table maximum: {value}")),
+                    }
+                }
+                _ => return Err(format!("Unknown option: {arg}")),
+            }
+        }
+
+        if options.ramp.is_some() && options.betting.is_some() {
+            return Err("Use either --ramp or --betting, not both".to_string());
+        }
+
+        // Betting by the count only makes sense for a player who keeps it
+        let bets_by_count =
+            options.ramp.is_some() || options.betting.is_some_and(|system| system.uses_count());
+        if bets_by_count && !strategy_given {
+            options.player = PlayerStrategy::Counting;
+        }
+        // Only the fast path is seeded, threaded and varies the bet
+        options.fast |= options.threads.is_some()
+            || options.seed.is_some()
+            || options.ramp.is_some()
+            || options.betting.is_some();
+        Ok(options)
+    }
+}
+
+pub fn simulate(rules: Rules, player: PlayerStrategy, hands: u64) -> SimStats {
+    run(Table::new(rules, player), hands)
+}
+
+pub fn simulate_seeded(rules: Rules, player: PlayerStrategy, hands: u64, seed: u64) -> SimStats {
+    run(Table::new(rules, player).with_seed(seed), hands)
+}
+
+fn run(mut table: Table, hands: u64) -> SimStats {
+    let mut stats = SimStats::new();
+
+    for _ in 0..hands {
+        stats.record(table.play_round());
+    }
+
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sim_stats() {
+        let mut stats = SimStats::new();
+        for result in [1.0, -1.0, 0.0, 1.5] {
+            stats.record(result);
+        }
+
+        assert_eq!(stats.hands, 4);
+        assert_eq!(stats.wins, 2);
+        assert_eq!(stats.losses, 1);
+        assert_eq!(stats.pushes, 1);
+        assert_eq!(stats.expected_value(), 0.375);
+        assert!((stats.standard_deviation() - 1.108678).abs() < 1e-6);
+
+        let (low, high) = stats.confidence_interval(1.96);
+        assert!(low < 0.375 && high > 0.375);
+        assert_eq!(stats.rate(stats.wins), 0.5);
+    }
+
+    #[test]
+    fn test_record_bet() {
+        let mut stats = SimStats::new();
+        stats.record(-1.0);
+        stats.record_bet(1.5, 4.0);
+
+        assert_eq!(stats.total, 5.0);
+        assert_eq!(stats.wagered, 5.0);
+        assert_eq!(stats.average_bet(), 2.5);
+        assert_eq!(stats.return_on_wagered(), 1.0);
+
+        // 1.5 units won and 2 units lost on 4 units bet
+        let mut ramped = SimStats::new();
+        ramped.record_bet(1.0, 1.0);
+        ramped.record_bet(-1.0, 2.0);
+        ramped.record_bet(0.5, 1.0);
+        assert_eq!(ramped.return_on_wagered(), -0.125);
+        assert!((ramped.expected_value() + 1.0 / 6.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut first = SimStats::new();
+        first.record(1.0);
+        let mut second = SimStats::new();
+        second.record(-1.0);
+        second.record(-1.0);
+
+        first.merge(&second);
+        assert_eq!(first.hands, 3);
+        assert_eq!(first.losses, 2);
+        assert_eq!(first.total, -1.0);
+    }
+
+    #[test]
+    fn test_round_results_are_bounded() {
+        let mut table = Table::new(Rules::default(), PlayerStrategy::Counting);
+
+        // Four split hands, all doubled, plus insurance is the most a round can move
+        for _ in 0..5_000 {
+            let result = table.play_round();
+            assert!((-8.5..=8.5).contains(&result));
+        }
+    }
+
+    #[test]
+    fn test_basic_strategy_beats_mimicking_the_dealer() {
+        let rules = Rules::default();
+        let basic = simulate_seeded(rules, PlayerStrategy::Basic, 100_000, 3);
+        let mimic = simulate_seeded(rules, PlayerStrategy::MimicDealer, 100_000, 3);
+
+        // Basic strategy is worth roughly five percent per hand over mimicking the dealer
+        assert!(basic.expected_value() > mimic.expected_value());
+        assert_eq!(basic.hands, 100_000);
+    }
+
+    #[test]
+    fn test_seeded_runs_repeat() {
+        let rules = Rules::default();
+        let first = simulate_seeded(rules, PlayerStrategy::Counting, 2_000, 11);
+        let second = simulate_seeded(rules, PlayerStrategy::Counting, 2_000, 11);
+
+        assert_eq!(first.total, second.total);
+        assert_eq!(first.wins, second.wins);
+    }
+
+    #[test]
+    fn test_sim_options_from_args() {
+        let args: Vec<String> = ["--hands", "2_000_000", "--strategy", "counting", "--h17"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let options = SimOptions::from_args(&args).expect("valid options");
+
+        assert_eq!(options.hands, 2_000_000);
+        assert_eq!(options.player, PlayerStrategy::Counting);
+        assert!(options.rules.dealer_hits_soft_17);
+
+        assert!(!options.fast);
+
+        let args: Vec<String> = ["--fast", "--threads", "8", "--seed", "99"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let options = SimOptions::from_args(&args).expect("valid options");
+        assert!(options.fast);
+        assert_eq!(options.threads, Some(8));
+        assert_eq!(options.seed, Some(99));
+
+        let bad: Vec<String> = vec!["--hands".to_string(), "0".to_string()];
+        assert!(SimOptions::from_args(&bad).is_err());
+
+        let args: Vec<String> = ["--betting", "kelly:0.5", "--sessions", "500"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let options = SimOptions::from_args(&args).expect("valid options");
+        assert_eq!(options.betting, Some(BettingSystem::Kelly(0.5)));
+        assert_eq!(options.sessions, 500);
+        assert_eq!(options.player, PlayerStrategy::Counting);
+        assert!(options.fast);
+
+        let both: Vec<String> = ["--betting", "flat", "--ramp", "1,2"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert!(SimOptions::from_args(&both).is_err());
+    }
+
+    #[test]
+    fn test_parse_player_strategy() {
+        assert_eq!(PlayerStrategy::parse("Basic"), Some(PlayerStrategy::Basic));
+        assert_eq!(
+            PlayerStrategy::parse("mimic"),
+            Some(PlayerStrategy::MimicDealer)
+        );
+        assert_eq!(PlayerStrategy::parse("martingale"), None);
+    }
+}
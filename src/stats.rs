@@ -0,0 +1,242 @@
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+
+use crate::outcome::Outcome;
+use crate::strategy::Action;
+
+const UPCARDS: [&str; 10] = ["2", "3", "4", "5", "6", "7", "8", "9", "T", "A"];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UpcardStats {
+    pub rounds: u64,
+    pub wins: u64,
+    pub player_busts: u64,
+    pub dealer_busts: u64,
+}
+
+// Results for a session, or a profile's whole life, built from each round's outcome
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlayerStats {
+    pub rounds: u64,
+    pub wins: u64,
+    pub losses: u64,
+    pub pushes: u64,
+    pub blackjacks: u64,
+    pub busts: u64,
+    pub doubles: u64,
+    pub splits: u64,
+    pub surrenders: u64,
+    pub wagered: u64,
+    pub net: i64,
+    pub biggest_win: i64,
+    pub biggest_loss: i64,
+    // Positive while winning, negative while losing; pushes leave it alone
+    pub streak: i64,
+    pub longest_winning_streak: u64,
+    pub longest_losing_streak: u64,
+    pub rebuys: u32,
+    // Indexed by the upcard's value, 2 to 11
+    pub by_upcard: [UpcardStats; 10],
+}
+
+impl PlayerStats {
+    pub fn new() -> Self {
+        PlayerStats::default()
+    }
+
+    pub fn record(
+        &mut self,
+        outcome: Outcome,
+        bet: u32,
+        delta: i64,
+        upcard: Option<u8>,
+        actions: &[Action],
+    ) {
+        self.rounds += 1;
+        self.wagered += bet as u64;
+        self.net += delta;
+        self.biggest_win = self.biggest_win.max(delta);
+        self.biggest_loss = self.biggest_loss.min(delta);
+
+        for action in actions {
+            match action {
+                Action::Double => self.doubles += 1,
+                Action::Split => self.splits += 1,
+                Action::Surrender => self.surrenders += 1,
+                Action::Hit | Action::Stand => {}
+            }
+        }
+
+        let won = match outcome {
+            Outcome::PlayerBlackjack => {
+                self.blackjacks += 1;
+                Some(true)
+            }
+            Outcome::DealerBust | Outcome::PlayerWins => Some(true),
+            Outcome::PlayerBust => {
+                self.busts += 1;
+                Some(false)
+            }
+            Outcome::DealerBlackjack | Outcome::DealerWins => Some(false),
+            Outcome::Push => None,
+        };
+        match won {
+            Some(true) => {
+                self.wins += 1;
+                self.streak = self.streak.max(0) + 1;
+                self.longest_winning_streak = self.longest_winning_streak.max(self.streak as u64);
+            }
+            Some(false) => {
+                self.losses += 1;
+                self.streak = self.streak.min(0) - 1;
+                self.longest_losing_streak =
+                    self.longest_losing_streak.max(self.streak.unsigned_abs());
+            }
+            None => self.pushes += 1,
+        }
+
+        if let Some(upcard) = upcard.filter(|value| (2..=11).contains(value)) {
+            let by_upcard = &mut self.by_upcard[upcard as usize - 2];
+            by_upcard.rounds += 1;
+            by_upcard.wins += u64::from(won == Some(true));
+            by_upcard.player_busts += u64::from(outcome == Outcome::PlayerBust);
+            by_upcard.dealer_busts += u64::from(outcome == Outcome::DealerBust);
+        }
+    }
+
+    pub fn win_rate(&self) -> f64 {
+        percent(self.wins, self.rounds)
+    }
+
+    pub fn render(&self, title: &str) -> String {
+        let mut text = format!("=== {title} ===\n");
+        if self.rounds == 0 {
+            text.push_str("No hands played yet.");
+            return text;
+        }
+
+        let rows = [
+            ("Hands played", self.rounds.to_string()),
+            ("Wins", format!("{} ({:.1}%)", self.wins, self.win_rate())),
+            ("Losses", self.losses.to_string()),
+            ("Pushes", self.pushes.to_string()),
+            ("Blackjacks", self.blackjacks.to_string()),
+            ("Busts", self.busts.to_string()),
+            ("Doubles", self.doubles.to_string()),
+            ("Splits", self.splits.to_string()),
+            ("Surrenders", self.surrenders.to_string()),
+            ("Wagered", format!("{} chips", self.wagered)),
+            ("Net result", format!("{:+} chips", self.net)),
+            ("Biggest win", format!("{:+} chips", self.biggest_win)),
+            ("Biggest loss", format!("{:+} chips", self.biggest_loss)),
+            (
+                "Longest streaks",
+                format!(
+                    "{} wins, {} losses",
+                    self.longest_winning_streak, self.longest_losing_streak
+                ),
+            ),
+        ];
+        for (label, value) in rows {
+            let _ = writeln!(text, "{label:<16} {value}");
+        }
+        if self.rebuys > 0 {
+            let _ = writeln!(text, "{:<16} {}", "Rebuys", self.rebuys);
+        }
+
+        let _ = write!(
+            text,
+            "\n{:<7} {:>6} {:>9} {:>11} {:>13}",
+            "Upcard", "Hands", "Won", "You busted", "Dealer busted"
+        );
+        for (name, upcard) in UPCARDS.iter().zip(&self.by_upcard) {
+            if upcard.rounds == 0 {
+                let _ = write!(text, "\n{name:<7} {:>6}", 0);
+                continue;
+            }
+            let _ = write!(
+                text,
+                "\n{name:<7} {:>6} {:>8.1}% {:>10.1}% {:>12.1}%",
+                upcard.rounds,
+                percent(upcard.wins, upcard.rounds),
+                percent(upcard.player_busts, upcard.rounds),
+                percent(upcard.dealer_busts, upcard.rounds)
+            );
+        }
+        text
+    }
+}
+
+fn percent(count: u64, total: u64) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    count as f64 * 100.0 / total as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record() {
+        let mut stats = PlayerStats::new();
+        stats.record(Outcome::PlayerBlackjack, 10, 15, Some(10), &[]);
+        stats.record(Outcome::PlayerBust, 10, -10, Some(6), &[Action::Hit]);
+        stats.record(Outcome::Push, 20, 0, Some(6), &[Action::Stand]);
+        stats.record(Outcome::DealerBust, 20, 20, Some(6), &[Action::Double]);
+
+        assert_eq!(stats.rounds, 4);
+        assert_eq!(stats.wins, 2);
+        assert_eq!(stats.losses, 1);
+        assert_eq!(stats.blackjacks, 1);
+        assert_eq!(stats.busts, 1);
+        assert_eq!(stats.doubles, 1);
+        assert_eq!(stats.wagered, 60);
+        assert_eq!(stats.net, 25);
+        assert_eq!(stats.biggest_win, 20);
+        assert_eq!(stats.biggest_loss, -10);
+        assert_eq!(stats.win_rate(), 50.0);
+
+        let six = stats.by_upcard[4];
+        assert_eq!((six.rounds, six.wins), (3, 1));
+        assert_eq!((six.player_busts, six.dealer_busts), (1, 1));
+        assert_eq!(stats.by_upcard[8].wins, 1);
+    }
+
+    #[test]
+    fn test_streaks() {
+        let mut stats = PlayerStats::new();
+        let results = [
+            Outcome::PlayerWins,
+            Outcome::DealerBust,
+            Outcome::Push,
+            Outcome::PlayerWins,
+            Outcome::DealerWins,
+            Outcome::PlayerBust,
+            Outcome::PlayerWins,
+        ];
+        for outcome in results {
+            stats.record(outcome, 10, 0, None, &[]);
+        }
+        assert_eq!(stats.longest_winning_streak, 3);
+        assert_eq!(stats.longest_losing_streak, 2);
+        assert_eq!(stats.streak, 1);
+    }
+
+    #[test]
+    fn test_render() {
+        let mut stats = PlayerStats::new();
+        assert!(stats.render("Session").contains("No hands played"));
+
+        stats.record(Outcome::DealerBust, 10, 10, Some(11), &[]);
+        let text = stats.render("Session");
+        assert!(text.starts_with("=== Session ==="));
+        assert!(text.contains("Net result       +10 chips"));
+        assert!(text.lines().last().unwrap().starts_with("A "));
+        assert!(text.contains("100.0%"));
+    }
+}
@@ -0,0 +1,755 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::card::Card;
+use crate::hand::Hand;
+
+pub const DEFAULT_INDEX_TABLE: &str = "\
+# Hi-Lo index plays, applied whenever a true count is available.
+# Each line: <hand> <dealer upcard> <true count condition> <action>
+# Hands are hard totals (16), soft hands (A7) or pairs (TT, 99, AA).
+# Conditions are >=, >, <= or < followed by a true count.
+# Lines are checked from the top, and the first one that applies wins.
+
+# Fab 4 surrenders
+14 T >=3 surrender
+15 T >=0 surrender
+15 9 >=2 surrender
+15 A >=1 surrender
+
+# Illustrious 18
+insurance >=3
+16 T >=0 stand
+15 T >=4 stand
+TT 5 >=5 split
+TT 6 >=4 split
+10 T >=4 double
+12 3 >=2 stand
+12 2 >=3 stand
+11 A >=1 double
+9 2 >=1 double
+10 A >=4 double
+9 7 >=3 double
+16 9 >=5 stand
+13 2 <-1 hit
+12 4 <0 hit
+12 5 <-2 hit
+12 6 <-1 hit
+13 3 <-2 hit
+";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Hit,
+    Stand,
+    Double,
+    Split,
+    Surrender,
+}
+
+impl Action {
+    pub fn parse(input: &str) -> Option<Action> {
+        match input.to_lowercase().as_str() {
+            "h" | "hit" => Some(Action::Hit),
+            "s" | "stand" => Some(Action::Stand),
+            "d" | "double" => Some(Action::Double),
+            "p" | "split" => Some(Action::Split),
+            "r" | "surrender" => Some(Action::Surrender),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Action::Hit => "Hit",
+            Action::Stand => "Stand",
+            Action::Double => "Double",
+            Action::Split => "Split",
+            Action::Surrender => "Surrender",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Available {
+    pub double: bool,
+    pub split: bool,
+    pub surrender: bool,
+}
+
+impl Available {
+    pub const ALL: Available = Available {
+        double: true,
+        split: true,
+        surrender: true,
+    };
+
+    pub const HIT_STAND: Available = Available {
+        double: false,
+        split: false,
+        surrender: false,
+    };
+
+    pub fn allows(&self, action: Action) -> bool {
+        match action {
+            Action::Hit | Action::Stand => true,
+            Action::Double => self.double,
+            Action::Split => self.split,
+            Action::Surrender => self.surrender,
+        }
+    }
+}
+
+// Pairs and soft hands are keyed by card value, so an Ace is 11
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HandKey {
+    Hard(u8),
+    Soft(u8),
+    Pair(u8),
+}
+
+impl HandKey {
+    pub fn of(hand: &Hand) -> HandKey {
+        if hand.is_pair() {
+            HandKey::Pair(hand.cards()[0].value())
+        } else {
+            HandKey::of_total(hand)
+        }
+    }
+
+    pub fn of_total(hand: &Hand) -> HandKey {
+        if hand.is_soft() {
+            HandKey::Soft(hand.value())
+        } else {
+            HandKey::Hard(hand.value())
+        }
+    }
+
+    // How a pair plays when it isn't split
+    pub fn total(self) -> HandKey {
+        match self {
+            HandKey::Pair(11) => HandKey::Soft(12),
+            HandKey::Pair(value) => HandKey::Hard(value * 2),
+            key => key,
+        }
+    }
+
+    pub fn parse(input: &str) -> Option<HandKey> {
+        let upper = input.to_uppercase();
+        let chars: Vec<char> = upper.chars().collect();
+
+        // Two card symbols (TT, A7) take priority over a total, so 99 is a pair
+        if let [first, second] = chars[..] {
+            let first = card_value(&first.to_string());
+            let second = card_value(&second.to_string());
+            match (first, second) {
+                (Some(first), Some(second)) if first == second => {
+                    return Some(HandKey::Pair(first))
+                }
+                (Some(11), Some(second)) if second < 10 => return Some(HandKey::Soft(11 + second)),
+                _ => {}
+            }
+        }
+
+        match upper.parse::<u8>() {
+            Ok(total) if (4..=21).contains(&total) => Some(HandKey::Hard(total)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for HandKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HandKey::Hard(total) => write!(f, "{total}"),
+            HandKey::Soft(total) => write!(f, "A{}", total - 11),
+            HandKey::Pair(value) => {
+                let card = upcard_label(*value);
+                write!(f, "{card}{card}")
+            }
+        }
+    }
+}
+
+fn card_value(input: &str) -> Option<u8> {
+    match input.to_uppercase().as_str() {
+        "A" => Some(11),
+        "T" | "10" => Some(10),
+        other => other.parse::<u8>().ok().filter(|v| (2..=9).contains(v)),
+    }
+}
+
+fn upcard_label(value: u8) -> String {
+    match value {
+        11 => "A".to_string(),
+        10 => "T".to_string(),
+        v => v.to_string(),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Condition {
+    AtLeast(f64),
+    Above(f64),
+    AtMost(f64),
+    Below(f64),
+}
+
+impl Condition {
+    pub fn holds(&self, true_count: f64) -> bool {
+        match *self {
+            Condition::AtLeast(index) => true_count >= index,
+            Condition::Above(index) => true_count > index,
+            Condition::AtMost(index) => true_count <= index,
+            Condition::Below(index) => true_count < index,
+        }
+    }
+
+    pub fn parse(input: &str) -> Option<Condition> {
+        let (build, number): (fn(f64) -> Condition, &str) =
+            if let Some(rest) = input.strip_prefix(">=") {
+                (Condition::AtLeast, rest)
+            } else if let Some(rest) = input.strip_prefix("<=") {
+                (Condition::AtMost, rest)
+            } else if let Some(rest) = input.strip_prefix('>') {
+                (Condition::Above, rest)
+            } else if let Some(rest) = input.strip_prefix('<') {
+                (Condition::Below, rest)
+            } else {
+                return None;
+            };
+        number.parse::<f64>().ok().map(build)
+    }
+
+    // The counts where this condition doesn't hold
+    pub fn opposite(&self) -> Condition {
+        match *self {
+            Condition::AtLeast(index) => Condition::Below(index),
+            Condition::Above(index) => Condition::AtMost(index),
+            Condition::AtMost(index) => Condition::Above(index),
+            Condition::Below(index) => Condition::AtLeast(index),
+        }
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Condition::AtLeast(index) => write!(f, ">= {index:+}"),
+            Condition::Above(index) => write!(f, "> {index:+}"),
+            Condition::AtMost(index) => write!(f, "<= {index:+}"),
+            Condition::Below(index) => write!(f, "< {index:+}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IndexPlay {
+    pub hand: HandKey,
+    pub upcard: u8,
+    pub condition: Condition,
+    pub action: Action,
+}
+
+impl fmt::Display for IndexPlay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} vs {} at TC {}: {}",
+            self.hand,
+            upcard_label(self.upcard),
+            self.condition,
+            self.action
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexTable {
+    pub plays: Vec<IndexPlay>,
+    pub insurance: Option<Condition>,
+}
+
+impl Default for IndexTable {
+    fn default() -> Self {
+        IndexTable::parse(DEFAULT_INDEX_TABLE).expect("default index table is valid")
+    }
+}
+
+impl IndexTable {
+    pub fn empty() -> Self {
+        IndexTable {
+            plays: Vec::new(),
+            insurance: None,
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut table = IndexTable::empty();
+
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let error = |message: &str| format!("Line {}: {message}: {line}", number + 1);
+            let fields: Vec<&str> = line.split_whitespace().collect();
+
+            if fields[0].eq_ignore_ascii_case("insurance") {
+                if fields.len() != 2 {
+                    return Err(error("expected 'insurance <condition>'"));
+                }
+                let condition =
+                    Condition::parse(fields[1]).ok_or_else(|| error("invalid condition"))?;
+                table.insurance = Some(condition);
+                continue;
+            }
+
+            if fields.len() != 4 {
+                return Err(error("expected '<hand> <upcard> <condition> <action>'"));
+            }
+            table.plays.push(IndexPlay {
+                hand: HandKey::parse(fields[0]).ok_or_else(|| error("invalid hand"))?,
+                upcard: card_value(fields[1]).ok_or_else(|| error("invalid upcard"))?,
+                condition: Condition::parse(fields[2]).ok_or_else(|| error("invalid condition"))?,
+                action: Action::parse(fields[3]).ok_or_else(|| error("invalid action"))?,
+            });
+        }
+
+        Ok(table)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|err| format!("Could not read {}: {err}", path.display()))?;
+        IndexTable::parse(&text)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Play {
+    Hit,
+    Stand,
+    DoubleOrHit,
+    DoubleOrStand,
+    Split,
+    SurrenderOrHit,
+}
+
+fn chart(key: HandKey, upcard: u8) -> Play {
+    match key {
+        HandKey::Pair(11) | HandKey::Pair(8) => Play::Split,
+        HandKey::Pair(10) => Play::Stand,
+        HandKey::Pair(9) => match upcard {
+            7 | 10 | 11 => Play::Stand,
+            _ => Play::Split,
+        },
+        HandKey::Pair(5) => chart(HandKey::Hard(10), upcard),
+        HandKey::Pair(6) if upcard <= 6 => Play::Split,
+        HandKey::Pair(4) if upcard == 5 || upcard == 6 => Play::Split,
+        HandKey::Pair(2) | HandKey::Pair(3) | HandKey::Pair(7) if upcard <= 7 => Play::Split,
+        HandKey::Pair(_) => Play::Hit,
+        HandKey::Soft(total) => match total {
+            19.. => Play::Stand,
+            18 => match upcard {
+                3..=6 => Play::DoubleOrStand,
+                2 | 7 | 8 => Play::Stand,
+                _ => Play::Hit,
+            },
+            17 if (3..=6).contains(&upcard) => Play::DoubleOrHit,
+            15 | 16 if (4..=6).contains(&upcard) => Play::DoubleOrHit,
+            13 | 14 if (5..=6).contains(&upcard) => Play::DoubleOrHit,
+            _ => Play::Hit,
+        },
+        HandKey::Hard(total) => match total {
+            17.. => Play::Stand,
+            16 if upcard >= 9 => Play::SurrenderOrHit,
+            15 if upcard == 10 => Play::SurrenderOrHit,
+            13..=16 if upcard <= 6 => Play::Stand,
+            12 if (4..=6).contains(&upcard) => Play::Stand,
+            11 if upcard <= 10 => Play::DoubleOrHit,
+            10 if upcard <= 9 => Play::DoubleOrHit,
+            9 if (3..=6).contains(&upcard) => Play::DoubleOrHit,
+            _ => Play::Hit,
+        },
+    }
+}
+
+fn resolve(play: Play, available: Available) -> Action {
+    match play {
+        Play::Hit => Action::Hit,
+        Play::Stand => Action::Stand,
+        Play::DoubleOrHit if available.double => Action::Double,
+        Play::DoubleOrHit => Action::Hit,
+        Play::DoubleOrStand if available.double => Action::Double,
+        Play::DoubleOrStand => Action::Stand,
+        Play::Split => Action::Split,
+        Play::SurrenderOrHit if available.surrender => Action::Surrender,
+        Play::SurrenderOrHit => Action::Hit,
+    }
+}
+
+pub fn basic_strategy(hand: &Hand, upcard: &Card, available: Available) -> Action {
+    basic_strategy_for(HandKey::of(hand), upcard.value(), available)
+}
+
+pub fn basic_strategy_for(key: HandKey, upcard: u8, available: Available) -> Action {
+    let mut play = chart(key, upcard);
+    if play == Play::Split && !available.split {
+        play = chart(key.total(), upcard);
+    }
+    resolve(play, available)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Recommendation {
+    pub action: Action,
+    pub basic: Action,
+    pub deviation: Option<IndexPlay>,
+}
+
+impl fmt::Display for Recommendation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.deviation {
+            Some(play) => write!(
+                f,
+                "{} (index play {play} overrides basic strategy: {})",
+                self.action, self.basic
+            ),
+            None => write!(f, "{}", self.action),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Strategy {
+    indices: IndexTable,
+}
+
+impl Strategy {
+    pub fn new() -> Self {
+        Strategy::default()
+    }
+
+    pub fn with_indices(indices: IndexTable) -> Self {
+        Strategy { indices }
+    }
+
+    pub fn indices(&self) -> &IndexTable {
+        &self.indices
+    }
+
+    pub fn recommend(
+        &self,
+        hand: &Hand,
+        upcard: &Card,
+        true_count: Option<f64>,
+        available: Available,
+    ) -> Recommendation {
+        self.recommend_for(HandKey::of(hand), upcard.value(), true_count, available)
+    }
+
+    pub fn recommend_for(
+        &self,
+        key: HandKey,
+        upcard: u8,
+        true_count: Option<f64>,
+        available: Available,
+    ) -> Recommendation {
+        let basic = basic_strategy_for(key, upcard, available);
+        let mut recommendation = Recommendation {
+            action: basic,
+            basic,
+            deviation: None,
+        };
+
+        let Some(true_count) = true_count else {
+            return recommendation;
+        };
+
+        // A pair that basic strategy doesn't split is played as its total
+        let total = match key {
+            HandKey::Pair(_) if basic != Action::Split => key.total(),
+            _ => key,
+        };
+
+        let applies =
+            |play: &&IndexPlay| (play.hand == key || play.hand == total) && play.upcard == upcard;
+
+        // A surrender index means surrendering at or above it, and playing the
+        // hand out as if surrender weren't offered below it
+        let mut played = basic;
+        if basic == Action::Surrender {
+            let surrender =
+                self.indices.plays.iter().filter(applies).find(|play| {
+                    play.action == Action::Surrender && !play.condition.holds(true_count)
+                });
+            if let Some(play) = surrender {
+                played = basic_strategy_for(
+                    key,
+                    upcard,
+                    Available {
+                        surrender: false,
+                        ..available
+                    },
+                );
+                recommendation.action = played;
+                recommendation.deviation = Some(IndexPlay {
+                    condition: play.condition.opposite(),
+                    action: played,
+                    ..*play
+                });
+            }
+        }
+
+        let deviation = self.indices.plays.iter().filter(applies).find(|play| {
+            available.allows(play.action)
+                // Hit and stand indices don't apply once basic strategy surrenders
+                && (played != Action::Surrender || play.action == Action::Surrender)
+                && play.condition.holds(true_count)
+        });
+
+        if let Some(play) = deviation {
+            if play.action != played {
+                recommendation.action = play.action;
+                recommendation.deviation = Some(*play);
+            }
+        }
+
+        recommendation
+    }
+
+    pub fn take_insurance(&self, true_count: Option<f64>) -> bool {
+        match (self.indices.insurance, true_count) {
+            (Some(condition), Some(true_count)) => condition.holds(true_count),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::{Rank, Suit};
+
+    fn card(rank: Rank) -> Card {
+        Card {
+            suit: Suit::Spades,
+            rank,
+        }
+    }
+
+    fn hand(ranks: &[Rank]) -> Hand {
+        let mut hand = Hand::new();
+        for &rank in ranks {
+            hand.add_card(card(rank));
+        }
+        hand
+    }
+
+    #[test]
+    fn test_basic_strategy_hard_totals() {
+        let sixteen = hand(&[Rank::Ten, Rank::Six]);
+        assert_eq!(
+            basic_strategy(&sixteen, &card(Rank::Six), Available::ALL),
+            Action::Stand
+        );
+        assert_eq!(
+            basic_strategy(&sixteen, &card(Rank::Ten), Available::ALL),
+            Action::Surrender
+        );
+        assert_eq!(
+            basic_strategy(&sixteen, &card(Rank::Ten), Available::HIT_STAND),
+            Action::Hit
+        );
+
+        let eleven = hand(&[Rank::Six, Rank::Five]);
+        assert_eq!(
+            basic_strategy(&eleven, &card(Rank::Ten), Available::ALL),
+            Action::Double
+        );
+        assert_eq!(
+            basic_strategy(&eleven, &card(Rank::Ace), Available::ALL),
+            Action::Hit
+        );
+    }
+
+    #[test]
+    fn test_basic_strategy_soft_and_pairs() {
+        let soft_18 = hand(&[Rank::Ace, Rank::Seven]);
+        assert_eq!(
+            basic_strategy(&soft_18, &card(Rank::Four), Available::ALL),
+            Action::Double
+        );
+        assert_eq!(
+            basic_strategy(&soft_18, &card(Rank::Four), Available::HIT_STAND),
+            Action::Stand
+        );
+        assert_eq!(
+            basic_strategy(&soft_18, &card(Rank::Nine), Available::ALL),
+            Action::Hit
+        );
+
+        let eights = hand(&[Rank::Eight, Rank::Eight]);
+        assert_eq!(
+            basic_strategy(&eights, &card(Rank::Ten), Available::ALL),
+            Action::Split
+        );
+
+        // Without splitting, 8-8 is played as hard 16
+        assert_eq!(
+            basic_strategy(&eights, &card(Rank::Ten), Available::HIT_STAND),
+            Action::Hit
+        );
+
+        let tens = hand(&[Rank::King, Rank::Queen]);
+        assert_eq!(
+            basic_strategy(&tens, &card(Rank::Six), Available::ALL),
+            Action::Stand
+        );
+    }
+
+    #[test]
+    fn test_index_play_overrides_basic_strategy() {
+        let strategy = Strategy::new();
+        let sixteen = hand(&[Rank::Ten, Rank::Six]);
+
+        let low = strategy.recommend(&sixteen, &card(Rank::Ten), Some(-1.0), Available::HIT_STAND);
+        assert_eq!(low.action, Action::Hit);
+        assert!(low.deviation.is_none());
+
+        let high = strategy.recommend(&sixteen, &card(Rank::Ten), Some(0.5), Available::HIT_STAND);
+        assert_eq!(high.action, Action::Stand);
+        assert_eq!(high.basic, Action::Hit);
+        assert!(high.deviation.is_some());
+        assert!(high.to_string().contains("overrides basic strategy"));
+    }
+
+    #[test]
+    fn test_no_true_count_means_basic_strategy() {
+        let strategy = Strategy::new();
+        let twelve = hand(&[Rank::Ten, Rank::Two]);
+
+        let recommendation = strategy.recommend(&twelve, &card(Rank::Three), None, Available::ALL);
+        assert_eq!(recommendation.action, Action::Hit);
+        assert!(recommendation.deviation.is_none());
+
+        let recommendation =
+            strategy.recommend(&twelve, &card(Rank::Three), Some(2.0), Available::ALL);
+        assert_eq!(recommendation.action, Action::Stand);
+    }
+
+    #[test]
+    fn test_fab_4_surrender_needs_surrender_offered() {
+        let strategy = Strategy::new();
+        let fourteen = hand(&[Rank::Ten, Rank::Four]);
+
+        let offered = strategy.recommend(&fourteen, &card(Rank::King), Some(3.0), Available::ALL);
+        assert_eq!(offered.action, Action::Surrender);
+
+        let not_offered = strategy.recommend(
+            &fourteen,
+            &card(Rank::King),
+            Some(3.0),
+            Available::HIT_STAND,
+        );
+        assert_eq!(not_offered.action, Action::Hit);
+    }
+
+    #[test]
+    fn test_surrender_index_falls_back_below_it() {
+        let strategy = Strategy::new();
+        let fifteen = hand(&[Rank::Ten, Rank::Five]);
+
+        let at = strategy.recommend(&fifteen, &card(Rank::Ten), Some(0.0), Available::ALL);
+        assert_eq!(at.action, Action::Surrender);
+        assert!(at.deviation.is_none());
+
+        let below = strategy.recommend(&fifteen, &card(Rank::Ten), Some(-1.0), Available::ALL);
+        assert_eq!(below.basic, Action::Surrender);
+        assert_eq!(below.action, Action::Hit);
+        let play = below.deviation.expect("the surrender index applies");
+        assert_eq!(play.condition, Condition::Below(0.0));
+        assert_eq!(play.action, Action::Hit);
+    }
+
+    #[test]
+    fn test_negative_indices_stand_at_the_index() {
+        let strategy = Strategy::new();
+        let thirteen = hand(&[Rank::Ten, Rank::Three]);
+
+        let at = strategy.recommend(&thirteen, &card(Rank::Two), Some(-1.0), Available::ALL);
+        assert_eq!(at.action, Action::Stand);
+        assert!(at.deviation.is_none());
+
+        let below = strategy.recommend(&thirteen, &card(Rank::Two), Some(-1.5), Available::ALL);
+        assert_eq!(below.action, Action::Hit);
+        assert!(below.deviation.is_some());
+    }
+
+    #[test]
+    fn test_insurance_index() {
+        let strategy = Strategy::new();
+        assert!(!strategy.take_insurance(None));
+        assert!(!strategy.take_insurance(Some(2.9)));
+        assert!(strategy.take_insurance(Some(3.0)));
+    }
+
+    #[test]
+    fn test_parse_index_table() {
+        let table = IndexTable::parse("# comment\n16 T >=0 stand\nA7 2 <1 hit\n99 A >=+2 split\n")
+            .expect("valid table");
+
+        assert_eq!(table.plays.len(), 3);
+        assert_eq!(table.plays[0].hand, HandKey::Hard(16));
+        assert_eq!(table.plays[0].upcard, 10);
+        assert_eq!(table.plays[0].condition, Condition::AtLeast(0.0));
+        assert_eq!(table.plays[1].hand, HandKey::Soft(18));
+        assert_eq!(table.plays[1].condition, Condition::Below(1.0));
+        assert_eq!(table.plays[2].hand, HandKey::Pair(9));
+        assert_eq!(table.plays[2].action, Action::Split);
+        assert!(table.insurance.is_none());
+    }
+
+    #[test]
+    fn test_parse_index_table_errors() {
+        assert!(IndexTable::parse("16 T stand").is_err());
+        assert!(IndexTable::parse("16 Z >=0 stand").is_err());
+        assert!(IndexTable::parse("16 T =0 stand").is_err());
+        assert!(IndexTable::parse("16 T >=0 dance").is_err());
+        assert!(IndexTable::parse("insurance").is_err());
+
+        let err = IndexTable::parse("\n\nQQQ T >=0 stand").unwrap_err();
+        assert!(err.starts_with("Line 3"));
+    }
+
+    #[test]
+    fn test_default_table_has_illustrious_18_and_fab_4() {
+        let table = IndexTable::default();
+        // The insurance index is the 18th entry of the Illustrious 18
+        assert_eq!(table.plays.len(), 17 + 4);
+        assert_eq!(table.insurance, Some(Condition::AtLeast(3.0)));
+    }
+
+    #[test]
+    fn test_pair_total() {
+        assert_eq!(HandKey::Pair(8).total(), HandKey::Hard(16));
+        assert_eq!(HandKey::Pair(11).total(), HandKey::Soft(12));
+        assert_eq!(HandKey::Soft(18).total(), HandKey::Soft(18));
+    }
+
+    #[test]
+    fn test_hand_key_display_round_trips() {
+        for text in ["16", "A7", "TT", "AA", "99"] {
+            let key = HandKey::parse(text).expect("valid hand");
+            assert_eq!(key.to_string(), text);
+        }
+    }
+}
@@ -0,0 +1,452 @@
+use std::io;
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::color;
+use crate::display::{card_label, render_card, render_cards_horizontal, render_hidden_card};
+use crate::game::Game;
+use crate::hand::Hand;
+
+const MAX_LOG: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    Betting,
+    Playing,
+    GameOver,
+}
+
+// A full-screen front end driving the same Game the line-mode interface plays
+pub struct App {
+    game: Game,
+    phase: Phase,
+    bet_input: String,
+    last_bet: Option<u32>,
+    show_hint: bool,
+    log: Vec<String>,
+    quit: bool,
+}
+
+impl App {
+    pub fn new(mut game: Game) -> Self {
+        game.start_session();
+        App {
+            game,
+            phase: Phase::Betting,
+            bet_input: String::new(),
+            last_bet: None,
+            show_hint: false,
+            log: vec!["Welcome to Blackjack! Type a bet and press Enter to deal.".to_string()],
+            quit: false,
+        }
+    }
+
+    pub fn into_game(self) -> Game {
+        self.game
+    }
+
+    fn log(&mut self, message: impl Into<String>) {
+        self.log.push(message.into());
+        if self.log.len() > MAX_LOG {
+            self.log.remove(0);
+        }
+    }
+
+    // The bet Enter places when nothing is typed: the betting system's
+    // suggestion, otherwise the last bet
+    fn default_bet(&mut self) -> Option<u32> {
+        self.game
+            .suggested_bet()
+            .map(|(_, bet)| bet)
+            .or(self.last_bet)
+    }
+
+    pub fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        if modifiers.contains(KeyModifiers::CONTROL) && code == KeyCode::Char('c') {
+            self.quit = true;
+            return;
+        }
+        match self.phase {
+            Phase::Betting => self.handle_bet_key(code),
+            Phase::Playing => self.handle_play_key(code),
+            Phase::GameOver => {
+                if matches!(code, KeyCode::Char('q') | KeyCode::Esc | KeyCode::Enter) {
+                    self.quit = true;
+                }
+            }
+        }
+    }
+
+    fn handle_bet_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Char(digit) if digit.is_ascii_digit() && self.bet_input.len() < 9 => {
+                self.bet_input.push(digit);
+            }
+            KeyCode::Backspace => {
+                self.bet_input.pop();
+            }
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Enter => {
+                let bet = match self.bet_input.parse::<u32>() {
+                    Ok(bet) => Some(bet),
+                    Err(_) => self.default_bet(),
+                };
+                self.bet_input.clear();
+                match bet {
+                    None | Some(0) => self.log("Enter a bet of at least 1 chip."),
+                    Some(bet) if bet > self.game.player_chips => {
+                        self.log("You don't have enough chips!")
+                    }
+                    Some(bet) => self.deal(bet),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_play_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Char('h') => {
+                if let Some(card) = self.game.hit() {
+                    self.log(format!("You drew: {}", card_label(&card)));
+                }
+                if self.game.player_hand.is_busted() {
+                    self.log("You busted!");
+                    self.finish_round(false);
+                }
+            }
+            KeyCode::Char('s') => {
+                self.game.stand();
+                self.finish_round(true);
+            }
+            KeyCode::Char('?') => self.show_hint = !self.show_hint,
+            KeyCode::Char('o') => self.game.show_odds = !self.game.show_odds,
+            KeyCode::Char('d' | 'p' | 'r') => self.log("This table only offers hit and stand."),
+            KeyCode::Char('q') | KeyCode::Esc => self.log("Finish the hand before quitting."),
+            _ => {}
+        }
+    }
+
+    fn deal(&mut self, bet: u32) {
+        self.game.current_bet = bet;
+        self.last_bet = Some(bet);
+        if self.game.reshuffle_if_low() {
+            self.log("Shuffling deck...");
+        }
+        self.game.review.start_round();
+        self.game.deal_initial_cards();
+        self.log(format!("You bet {bet} chips."));
+        self.phase = Phase::Playing;
+
+        if self.game.player_hand.is_blackjack() {
+            self.log("Blackjack!");
+            self.finish_round(true);
+        }
+    }
+
+    fn finish_round(&mut self, dealer_plays: bool) {
+        // The table shows the dealer's whole hand once the round is over, so the hole
+        // card is counted even when the dealer doesn't need to play
+        self.game.reveal_hole_card();
+        if dealer_plays {
+            for card in self.game.dealer_turn() {
+                self.log(format!("Dealer drew: {}", card_label(&card)));
+            }
+        }
+
+        let (outcome, _, saved) = self.game.settle_round();
+        self.log(outcome.message());
+        self.log(format!("You now have {} chips.", self.game.player_chips));
+        if let Err(err) = saved {
+            self.log(format!("Warning: {err}"));
+        }
+
+        if self.game.player_chips == 0 {
+            self.log("You're out of chips! Game over. Press q to quit.");
+            self.phase = Phase::GameOver;
+        } else {
+            self.phase = Phase::Betting;
+        }
+    }
+
+    pub fn draw(&self, frame: &mut Frame) {
+        let [main, log] =
+            Layout::vertical([Constraint::Min(15), Constraint::Length(8)]).areas(frame.area());
+        let [table, side] =
+            Layout::horizontal([Constraint::Min(40), Constraint::Length(32)]).areas(main);
+        // Room for a row of cards, whatever art they're drawn with, plus the
+        // total and the border
+        let hand_height = render_hidden_card().len() as u16 + 3;
+        let [dealer, player, controls] = Layout::vertical([
+            Constraint::Length(hand_height),
+            Constraint::Min(hand_height),
+            Constraint::Length(3),
+        ])
+        .areas(table);
+        let [bankroll, count, hint] = Layout::vertical([
+            Constraint::Length(6),
+            Constraint::Length(5),
+            Constraint::Min(4),
+        ])
+        .areas(side);
+
+        frame.render_widget(self.dealer_panel(), dealer);
+        frame.render_widget(self.player_panel(), player);
+        frame.render_widget(self.controls_panel(), controls);
+        frame.render_widget(self.bankroll_panel(), bankroll);
+        frame.render_widget(self.count_panel(), count);
+        frame.render_widget(self.hint_panel(), hint);
+        frame.render_widget(self.log_panel(log), log);
+    }
+
+    fn dealer_panel(&self) -> Paragraph<'_> {
+        let hand = &self.game.dealer_hand;
+        let text = if hand.cards().is_empty() {
+            String::new()
+        } else if self.phase == Phase::Playing {
+            let mut cards = vec![render_hidden_card()];
+            cards.extend(hand.cards()[1..].iter().map(render_card));
+            let upcard = self
+                .game
+                .dealer_upcard()
+                .map(card_label)
+                .unwrap_or_default();
+            format!("{}\nShowing: {upcard}", render_cards_horizontal(&cards))
+        } else {
+            hand_text(hand)
+        };
+        Paragraph::new(text).block(Block::bordered().title(" Dealer "))
+    }
+
+    fn player_panel(&self) -> Paragraph<'_> {
+        Paragraph::new(hand_text(&self.game.player_hand))
+            .block(Block::bordered().title(" Your Hand "))
+    }
+
+    fn controls_panel(&self) -> Paragraph<'_> {
+        let text = match self.phase {
+            Phase::Betting => {
+                let default = match self.game.betting.as_ref() {
+                    Some(_) => " (empty for the suggested bet)".to_string(),
+                    None => match self.last_bet {
+                        Some(bet) => format!(" (empty to bet {bet} again)"),
+                        None => String::new(),
+                    },
+                };
+                format!("Bet: {}_  Enter deals{default}  q quits", self.bet_input)
+            }
+            Phase::Playing => "h hit  s stand  ? hint  o odds".to_string(),
+            Phase::GameOver => "Out of chips. Press q to quit.".to_string(),
+        };
+        Paragraph::new(text).block(Block::bordered().title(" Controls "))
+    }
+
+    fn bankroll_panel(&self) -> Paragraph<'_> {
+        let stats = &self.game.session_stats;
+        let lines = vec![
+            Line::from(format!("Chips:    {}", self.game.player_chips)),
+            Line::from(format!("Bet:      {}", self.game.current_bet)),
+            Line::from(format!("Session:  {:+}", stats.net)),
+            Line::from(format!("Hands:    {} ({} won)", stats.rounds, stats.wins)),
+        ];
+        Paragraph::new(lines).block(Block::bordered().title(" Bankroll "))
+    }
+
+    fn count_panel(&self) -> Paragraph<'_> {
+        let lines = vec![
+            Line::from(format!("Running:  {:+}", self.game.running_count.value())),
+            Line::from(format!("True:     {:+.1}", self.game.true_count())),
+            Line::from(format!(
+                "Shoe:     {} cards left",
+                self.game.deck.cards_remaining()
+            )),
+        ];
+        Paragraph::new(lines).block(Block::bordered().title(" Count "))
+    }
+
+    fn hint_panel(&self) -> Paragraph<'_> {
+        let mut sections = Vec::new();
+        if self.phase == Phase::Playing {
+            if self.show_hint {
+                sections.extend(self.game.hint());
+            }
+            if self.game.show_odds {
+                sections.extend(self.game.odds());
+            }
+        }
+        if sections.is_empty() {
+            sections.push("Press ? for a hint or o for the odds during a hand.".to_string());
+        }
+        Paragraph::new(sections.join("\n\n"))
+            .wrap(Wrap { trim: false })
+            .block(Block::bordered().title(" Hint "))
+    }
+
+    // Shows the newest lines that fit, so the log scrolls as it grows
+    fn log_panel(&self, area: Rect) -> Paragraph<'_> {
+        let visible = area.height.saturating_sub(2) as usize;
+        let start = self.log.len().saturating_sub(visible);
+        let lines: Vec<Line> = self.log[start..]
+            .iter()
+            .map(|line| Line::from(line.as_str()))
+            .collect();
+        Paragraph::new(lines).block(Block::bordered().title(" Log "))
+    }
+
+    fn event_loop(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        while !self.quit {
+            terminal.draw(|frame| self.draw(frame))?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    self.handle_key(key.code, key.modifiers);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn hand_text(hand: &Hand) -> String {
+    if hand.cards().is_empty() {
+        return String::new();
+    }
+    hand.to_string()
+}
+
+// Takes over the terminal until the player quits, then prints the session
+// summary the line-mode interface ends with
+pub fn run(game: Game) -> Result<(), String> {
+    // The panels are drawn by ratatui, which would show escape codes as text
+    let colored = color::enabled();
+    color::set_enabled(false);
+    let mut terminal = ratatui::init();
+    let mut app = App::new(game);
+    let result = app.event_loop(&mut terminal);
+    ratatui::restore();
+    color::set_enabled(colored);
+    result.map_err(|err| format!("Terminal error: {err}"))?;
+
+    app.into_game().print_summary();
+    println!("Thanks for playing!");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deck::Deck;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    fn press(app: &mut App, keys: &str) {
+        for key in keys.chars() {
+            let code = match key {
+                '\n' => KeyCode::Enter,
+                _ => KeyCode::Char(key),
+            };
+            app.handle_key(code, KeyModifiers::NONE);
+        }
+    }
+
+    fn screen(app: &App) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        buffer
+            .content()
+            .chunks(buffer.area.width as usize)
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    // The first hand from this shoe isn't a blackjack, so the player gets to act
+    const SEED: u64 = 1;
+
+    fn seeded_game() -> Game {
+        let mut game = Game::new();
+        game.deck = Deck::new();
+        game.deck.shuffle_seeded(SEED);
+        game.shoe_seed = Some(SEED);
+        game
+    }
+
+    #[test]
+    fn test_bet_and_play_a_round() {
+        let mut app = App::new(seeded_game());
+        press(&mut app, "10\n");
+        assert_eq!(app.game.current_bet, 10);
+        assert!(app.log.iter().any(|line| line == "You bet 10 chips."));
+
+        assert_eq!(app.phase, Phase::Playing);
+        assert_eq!(app.game.player_hand.cards().len(), 2);
+        press(&mut app, "q");
+        assert_eq!(app.phase, Phase::Playing);
+        assert!(!app.quit);
+        press(&mut app, "s");
+        assert_eq!(app.phase, Phase::Betting);
+        assert_eq!(app.game.session_stats.rounds, 1);
+        assert_eq!(app.game.balances.len(), 2);
+
+        // Enter on its own bets the same again
+        press(&mut app, "\n");
+        assert_eq!(app.game.current_bet, 10);
+    }
+
+    #[test]
+    fn test_bust_counts_the_hole_card() {
+        let mut app = App::new(seeded_game());
+        press(&mut app, "10\n");
+        while app.phase == Phase::Playing {
+            press(&mut app, "h");
+        }
+        assert!(app.game.player_hand.is_busted());
+
+        // Every card on the table has been seen, including the hole card
+        let on_table = app.game.player_hand.cards().len() + app.game.dealer_hand.cards().len();
+        assert_eq!(app.game.running_count.cards_seen(), on_table);
+    }
+
+    #[test]
+    fn test_invalid_bets() {
+        let mut app = App::new(Game::new());
+        press(&mut app, "\n");
+        assert_eq!(app.log.last().unwrap(), "Enter a bet of at least 1 chip.");
+        press(&mut app, "99999\n");
+        assert_eq!(app.log.last().unwrap(), "You don't have enough chips!");
+        assert_eq!(app.phase, Phase::Betting);
+        press(&mut app, "q");
+        assert!(app.quit);
+    }
+
+    #[test]
+    fn test_draw_panels() {
+        let mut app = App::new(seeded_game());
+        let text = screen(&app);
+        for title in [
+            "Dealer",
+            "Your Hand",
+            "Controls",
+            "Bankroll",
+            "Count",
+            "Hint",
+            "Log",
+        ] {
+            assert!(text.contains(title), "missing {title}");
+        }
+        assert!(text.contains("Chips:    1000"));
+
+        press(&mut app, "25\n");
+        assert_eq!(app.phase, Phase::Playing);
+        let text = screen(&app);
+        assert!(text.contains("░░░"));
+        let upcard = card_label(app.game.dealer_upcard().unwrap());
+        assert!(text.contains(&format!("Showing: {upcard}")), "{text}");
+        press(&mut app, "?");
+        assert!(screen(&app).contains("Hint:"));
+    }
+}
@@ -0,0 +1,297 @@
+use crate::card::Card;
+use crate::deck::{self, Deck};
+use crate::display::{card_label, render_outcome};
+use crate::hand::Hand;
+use crate::history::{PlayerAction, RoundRecord};
+use crate::outcome::Outcome;
+use crate::strategy::{Action, Available, Strategy};
+
+// The hand and shoe just before one of the player's recorded actions
+pub struct DecisionPoint {
+    pub chosen: Action,
+    pub hand: Hand,
+    pub deck: Deck,
+}
+
+// A recorded round replayed with a different action at one decision point
+pub struct Branch {
+    pub action: Action,
+    pub actions: Vec<PlayerAction>,
+    pub player_hand: Hand,
+    pub dealer_hand: Hand,
+    pub outcome: Outcome,
+    pub chip_delta: i64,
+}
+
+pub struct RoundBranches {
+    record: RoundRecord,
+    dealer: Hand,
+    points: Vec<DecisionPoint>,
+}
+
+// Rebuilds the shoe the round was dealt from and checks it deals the recorded cards
+fn rebuild_shoe(record: &RoundRecord) -> Result<Deck, String> {
+    let seed = record
+        .seed
+        .ok_or("This round was recorded without a shoe seed, so its shoe can't be rebuilt")?;
+    let mut deck = Deck::with_decks(record.rules.decks);
+    deck.shuffle_seeded(seed);
+    for _ in 0..record.shoe_position {
+        deck.deal();
+    }
+
+    if record.player_cards.len() < 2 || record.dealer_cards.len() < 2 {
+        return Err("The round doesn't have two cards in each hand".to_string());
+    }
+    // Checks the whole rest of the shoe, including cards a different play could draw
+    if let Some(hash) = record.shoe_hash {
+        if deck::order_hash(deck.upcoming()) != hash {
+            return Err("The rebuilt shoe isn't the one the round was dealt from, \
+                 so it was shuffled by a different version of the game"
+                .to_string());
+        }
+    }
+
+    let mut check = deck.clone();
+    for card in record.dealt_cards() {
+        if check.deal().map(|next| next.code()) != Some(card.code()) {
+            return Err("The rebuilt shoe doesn't match the recorded cards".to_string());
+        }
+    }
+    Ok(deck)
+}
+
+impl RoundBranches {
+    pub fn new(record: &RoundRecord) -> Result<RoundBranches, String> {
+        let mut deck = rebuild_shoe(record)?;
+        let mut player = Hand::new();
+        let mut dealer = Hand::new();
+        for _ in 0..2 {
+            player.add_card(deck.deal().expect("checked against the record"));
+            dealer.add_card(deck.deal().expect("checked against the record"));
+        }
+
+        let mut points = Vec::new();
+        for action in &record.actions {
+            points.push(DecisionPoint {
+                chosen: action.action,
+                hand: player.clone(),
+                deck: deck.clone(),
+            });
+            if action.card.is_some() {
+                player.add_card(deck.deal().expect("checked against the record"));
+            }
+        }
+
+        Ok(RoundBranches {
+            record: record.clone(),
+            dealer,
+            points,
+        })
+    }
+
+    pub fn points(&self) -> &[DecisionPoint] {
+        &self.points
+    }
+
+    // Takes the new action at the chosen point, then plays on by basic strategy
+    // with the same cards still to come
+    pub fn play(&self, point: usize, action: Action, strategy: &Strategy) -> Branch {
+        let point = &self.points[point];
+        let mut deck = point.deck.clone();
+        let mut player_hand = point.hand.clone();
+        let mut dealer_hand = self.dealer.clone();
+        let upcard: Card = dealer_hand.cards()[1];
+
+        let mut actions = Vec::new();
+        let mut next = action;
+        loop {
+            if next != Action::Hit {
+                actions.push(PlayerAction {
+                    action: Action::Stand,
+                    card: None,
+                });
+                break;
+            }
+            let card = deck.deal();
+            actions.push(PlayerAction {
+                action: Action::Hit,
+                card,
+            });
+            let Some(card) = card else {
+                break;
+            };
+            player_hand.add_card(card);
+            if player_hand.is_busted() {
+                break;
+            }
+            next = strategy
+                .recommend(&player_hand, &upcard, None, Available::HIT_STAND)
+                .action;
+        }
+
+        if !player_hand.is_busted() {
+            while self.record.rules.dealer_hits(&dealer_hand) {
+                let Some(card) = deck.deal() else {
+                    break;
+                };
+                dealer_hand.add_card(card);
+            }
+        }
+
+        let outcome = Outcome::of(&player_hand, &dealer_hand);
+        Branch {
+            action,
+            actions,
+            player_hand,
+            dealer_hand,
+            outcome,
+            chip_delta: outcome.chip_delta(self.record.bet, &self.record.rules),
+        }
+    }
+}
+
+pub fn describe_point(point: &DecisionPoint) -> String {
+    let hand = &point.hand;
+    let cards: Vec<String> = hand.cards().iter().map(|card| card.to_string()).collect();
+    format!(
+        "{} ({}): you chose {:?}",
+        cards.join(" "),
+        hand.value(),
+        point.chosen
+    )
+}
+
+pub fn render_branch(branch: &Branch, recorded_delta: i64) -> String {
+    let actions: Vec<String> = branch
+        .actions
+        .iter()
+        .map(|action| match action.card {
+            Some(card) => format!("{:?} ({})", action.action, card_label(&card)),
+            None => format!("{:?}", action.action),
+        })
+        .collect();
+    [
+        format!("=== What if you had chosen {:?} ===", branch.action),
+        String::new(),
+        "=== Dealer's Hand ===".to_string(),
+        branch.dealer_hand.to_string(),
+        String::new(),
+        "=== Your Hand ===".to_string(),
+        branch.player_hand.to_string(),
+        String::new(),
+        match actions.len() {
+            1 => format!("You: {}", actions[0]),
+            _ => format!(
+                "You: {} (by basic strategy after the first)",
+                actions.join(", ")
+            ),
+        },
+        render_outcome(branch.outcome),
+        format!(
+            "Chips: {:+} instead of {:+}",
+            branch.chip_delta, recorded_delta
+        ),
+    ]
+    .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::fixtures;
+    use crate::rules::Rules;
+
+    // Plays a round from a seeded shoe the way the game records it
+    fn recorded_round(seed: u64, hits: usize) -> RoundRecord {
+        let mut deck = Deck::new();
+        deck.shuffle_seeded(seed);
+        for _ in 0..7 {
+            deck.deal();
+        }
+        let shoe_hash = Some(deck::order_hash(deck.upcoming()));
+        let mut player = Vec::new();
+        let mut dealer = Vec::new();
+        for _ in 0..2 {
+            player.push(deck.deal().unwrap());
+            dealer.push(deck.deal().unwrap());
+        }
+        let mut actions: Vec<PlayerAction> = (0..hits)
+            .map(|_| PlayerAction {
+                action: Action::Hit,
+                card: deck.deal(),
+            })
+            .collect();
+        actions.push(PlayerAction {
+            action: Action::Stand,
+            card: None,
+        });
+
+        RoundRecord {
+            seed: Some(seed),
+            shoe_position: 7,
+            shoe_hash,
+            player_cards: player,
+            dealer_cards: dealer,
+            actions,
+            dealer_draws: Vec::new(),
+            outcome: Outcome::Push,
+            chip_delta: 0,
+            chips: 1000,
+            ..fixtures::record()
+        }
+    }
+
+    fn shoe_after(seed: u64, dealt: usize) -> Vec<String> {
+        let mut deck = Deck::new();
+        deck.shuffle_seeded(seed);
+        let codes: Vec<String> = deck.cards().iter().rev().map(|card| card.code()).collect();
+        codes[dealt..].to_vec()
+    }
+
+    #[test]
+    fn test_branch_uses_the_same_shoe() {
+        let record = recorded_round(99, 1);
+        let branches = RoundBranches::new(&record).expect("shoe rebuilt");
+        assert_eq!(branches.points().len(), 2);
+        assert_eq!(branches.points()[0].chosen, Action::Hit);
+
+        // Standing instead of the hit leaves that card for the dealer
+        let branch = branches.play(0, Action::Stand, &Strategy::new());
+        assert_eq!(branch.player_hand.cards().len(), 2);
+        let draws: Vec<String> = branch.dealer_hand.cards()[2..]
+            .iter()
+            .map(|card| card.code())
+            .collect();
+        assert_eq!(draws, shoe_after(99, 11)[..draws.len()]);
+
+        // Hitting instead of the final stand draws the next cards in the shoe
+        let branch = branches.play(1, Action::Hit, &Strategy::new());
+        assert_eq!(branch.actions[0].action, Action::Hit);
+        let hits = branch.actions.iter().filter(|a| a.card.is_some()).count();
+        assert_eq!(branch.player_hand.cards().len(), 3 + hits);
+        assert_eq!(branch.player_hand.cards()[3].code(), shoe_after(99, 12)[0]);
+        assert_eq!(
+            branch.chip_delta,
+            branch.outcome.chip_delta(10, &Rules::single_deck())
+        );
+    }
+
+    #[test]
+    fn test_shoe_must_match_the_record() {
+        let mut record = recorded_round(5, 0);
+        assert!(RoundBranches::new(&record).is_ok());
+
+        // A shoe that deals the round's cards but differs later on is still caught
+        record.shoe_hash = record.shoe_hash.map(|hash| hash ^ 1);
+        let err = RoundBranches::new(&record).err().unwrap();
+        assert!(err.contains("different version"), "{err}");
+        record.shoe_hash = None;
+        assert!(RoundBranches::new(&record).is_ok());
+
+        record.seed = Some(6);
+        assert!(RoundBranches::new(&record).is_err());
+        record.seed = None;
+        assert!(RoundBranches::new(&record).is_err());
+    }
+}